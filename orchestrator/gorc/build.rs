@@ -0,0 +1,62 @@
+//! Capture build metadata reported by `gorc version`
+
+use std::{
+    env,
+    path::PathBuf,
+    process::Command,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+fn main() {
+    println!("cargo:rustc-env=GORC_GIT_COMMIT={}", git_commit());
+    println!("cargo:rustc-env=GORC_BUILD_TIMESTAMP={}", build_timestamp());
+
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    for path in git_paths() {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+}
+
+/// Run git in the crate directory, returning its trimmed output on success
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let output = String::from_utf8(output.stdout).ok()?;
+    Some(output.trim().to_string())
+}
+
+/// Short hash of the checked out commit, `unknown` outside of a git checkout
+fn git_commit() -> String {
+    git(&["rev-parse", "--short", "HEAD"]).unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Files which change when a different commit is checked out
+fn git_paths() -> Vec<PathBuf> {
+    let dir = match git(&["rev-parse", "--git-dir"]) {
+        Some(dir) => PathBuf::from(dir),
+        None => return Vec::new(),
+    };
+
+    let mut paths = vec![dir.join("HEAD")];
+    if let Some(head) = git(&["symbolic-ref", "-q", "HEAD"]) {
+        paths.push(dir.join(head));
+    }
+    paths
+}
+
+/// RFC 3339 UTC build time, honouring `SOURCE_DATE_EPOCH` for reproducible
+/// builds.
+fn build_timestamp() -> String {
+    let time = env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+        .unwrap_or_else(SystemTime::now);
+
+    humantime::format_rfc3339_seconds(time).to_string()
+}
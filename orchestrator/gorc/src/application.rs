@@ -1,11 +1,17 @@
 //! Gorc Abscissa Application
 
-use crate::{commands::GorcCmd, config::GorcConfig};
+use crate::{commands::EntryPoint, config::GorcConfig};
 use abscissa_core::{
     application::{self, AppCell},
     config::{self, CfgCell},
-    trace, Application, EntryPoint, FrameworkError, StandardPaths,
+    terminal::component::Terminal,
+    Application, Component, FrameworkError, FrameworkErrorKind, StandardPaths,
 };
+use std::{env, io};
+use tracing_subscriber::{EnvFilter, FmtSubscriber};
+
+/// Environment variable the log filter is read from
+pub const LOG_ENV_VAR: &str = "GORC_LOG";
 
 /// Application state
 pub static APP: AppCell<GorcApp> = AppCell::new();
@@ -35,7 +41,7 @@ impl Default for GorcApp {
 
 impl Application for GorcApp {
     /// Entrypoint command for this application.
-    type Cmd = EntryPoint<GorcCmd>;
+    type Cmd = EntryPoint;
 
     /// Application configuration.
     type Cfg = GorcConfig;
@@ -79,12 +85,39 @@ impl Application for GorcApp {
         Ok(())
     }
 
-    /// Get tracing configuration from command-line options
-    fn tracing_config(&self, command: &EntryPoint<GorcCmd>) -> trace::Config {
-        if command.verbose {
-            trace::Config::verbose()
-        } else {
-            trace::Config::default()
-        }
+    /// Initialize the terminal and tracing subsystems.
+    ///
+    /// Unlike the framework's tracing component, logs are written to stderr
+    /// so stdout only carries command output and stays safe to pipe.
+    fn framework_components(
+        &mut self,
+        command: &Self::Cmd,
+    ) -> Result<Vec<Box<dyn Component<Self>>>, FrameworkError> {
+        let terminal = Terminal::new(self.term_colors(command));
+
+        let filter = EnvFilter::try_new(log_filter(command))
+            .map_err(|e| FrameworkErrorKind::ComponentError.context(e))?;
+
+        FmtSubscriber::builder()
+            .with_env_filter(filter)
+            .with_writer(io::stderr)
+            .with_ansi(atty::is(atty::Stream::Stderr))
+            .try_init()
+            .map_err(|e| FrameworkErrorKind::ComponentError.context(e))?;
+
+        Ok(vec![Box::new(terminal)])
     }
 }
+
+/// Log filter from `--log-level`, `GORC_LOG` or `--verbose`, in that order
+fn log_filter(command: &EntryPoint) -> String {
+    if let Some(level) = &command.log_level {
+        return level.clone();
+    }
+
+    if let Ok(level) = env::var(LOG_ENV_VAR) {
+        return level;
+    }
+
+    if command.verbose { "debug" } else { "info" }.to_owned()
+}
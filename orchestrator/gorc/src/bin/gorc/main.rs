@@ -3,9 +3,19 @@
 #![deny(warnings, missing_docs, trivial_casts, unused_qualifications)]
 #![forbid(unsafe_code)]
 
-use gorc::application::APP;
+use abscissa_core::Application;
+use gorc::{
+    application::{GorcApp, APP},
+    commands::EntryPoint,
+};
+use std::{env, process};
 
 /// Boot Gorc
+///
+/// Like `abscissa_core::boot`, but lets the entry point fill in a missing
+/// subcommand before the arguments are parsed.
 fn main() {
-    abscissa_core::boot(&APP);
+    let args = EntryPoint::with_default_subcommand(env::args().skip(1).collect());
+    GorcApp::run(&APP, args);
+    process::exit(0);
 }
@@ -10,6 +10,8 @@
 //! See the `impl Configurable` below for how to specify the path to the
 //! application's configuration file.
 
+mod completions;
+mod config;
 mod deploy;
 mod keys;
 mod query;
@@ -19,18 +21,184 @@ mod tx;
 mod version;
 
 use self::{
-    keys::KeysCmd, query::QueryCmd, start::StartCmd, tests::TestsCmd, tx::TxCmd,
-    version::VersionCmd,
+    completions::CompletionsCmd, config::ConfigCmd, keys::KeysCmd, query::QueryCmd,
+    start::StartCmd, tests::TestsCmd, tx::TxCmd, version::VersionCmd,
 };
-use crate::config::GorcConfig;
+use crate::config::{GorcConfig, KeystoreBackend, KeystoreFormat};
 use abscissa_core::{
-    Command, Configurable, Help, Options, Runnable,
+    command::Usage, Command, Configurable, FrameworkError, FrameworkErrorKind, Help, Options,
+    Runnable,
 };
 use std::path::PathBuf;
+use toml::Value;
 
 /// Gorc Configuration Filename
 pub const CONFIG_FILE: &str = "gorc.toml";
 
+/// Toplevel entrypoint command.
+///
+/// Mirrors abscissa's `EntryPoint`, adding global options which override
+/// values from the configuration file.
+#[derive(Debug, Options)]
+pub struct EntryPoint {
+    /// Path to the configuration file
+    #[options(short = "c", help = "path to configuration file")]
+    pub config: Option<PathBuf>,
+
+    /// Obtain help about the current command
+    #[options(short = "h", help = "print help message")]
+    pub help: bool,
+
+    /// Increase verbosity setting
+    #[options(short = "v", help = "be verbose")]
+    pub verbose: bool,
+
+    /// Print version information and exit
+    #[options(short = "V", help = "print version information")]
+    pub version: bool,
+
+    /// Log filter, overriding `GORC_LOG` and `--verbose`
+    #[options(no_short, help = "log level or filter (e.g. debug, gorc=trace)")]
+    pub log_level: Option<String>,
+
+    /// Keystore directory, overriding `keystore` from the config file
+    #[options(no_short, help = "path to keystore directory")]
+    pub keystore: Option<String>,
+
+    /// Where private keys are kept, overriding `keystore_backend`
+    #[options(no_short, help = "where private keys are kept (file|os)")]
+    pub keystore_backend: Option<KeystoreBackend>,
+
+    /// Format of new Ethereum keys, overriding `keystore_format`
+    #[options(no_short, help = "format of new eth keys (pem|json)")]
+    pub keystore_format: Option<KeystoreFormat>,
+
+    /// Refuse commands which change the keystore, overriding
+    /// `keystore_readonly`
+    #[options(no_short, help = "refuse commands which change the keystore")]
+    pub keystore_readonly: bool,
+
+    /// Subcommand to execute.
+    #[options(command)]
+    pub command: Option<GorcCmd>,
+}
+
+impl Runnable for EntryPoint {
+    fn run(&self) {
+        if self.version {
+            return VersionCmd::default().run();
+        }
+
+        match &self.command {
+            Some(GorcCmd::Config(ConfigCmd::Validate(cmd))) => {
+                cmd.validate(self.config_file().as_deref(), &self.overrides())
+            }
+            Some(command) => command.run(),
+            None => GorcCmd::print_usage_and_exit(&[]),
+        }
+    }
+}
+
+impl Command for EntryPoint {
+    fn name() -> &'static str {
+        GorcCmd::name()
+    }
+
+    fn description() -> &'static str {
+        GorcCmd::description()
+    }
+
+    fn version() -> &'static str {
+        GorcCmd::version()
+    }
+
+    fn authors() -> &'static str {
+        GorcCmd::authors()
+    }
+
+    fn subcommand_usage(command: &str) -> Option<Usage> {
+        GorcCmd::subcommand_usage(command)
+    }
+}
+
+impl EntryPoint {
+    /// Command-line arguments to parse: `gorc keys`, `gorc keys eth` and
+    /// `gorc keys cosmos` without a subcommand get `menu` appended, so they
+    /// open the interactive menu rather than failing to parse.
+    pub fn with_default_subcommand(mut args: Vec<String>) -> Vec<String> {
+        if Self::parse_args_default(&args).is_ok() {
+            return args;
+        }
+
+        args.push("menu".to_owned());
+        let opens_menu = match Self::parse_args_default(&args) {
+            Ok(entry) => matches!(entry.command, Some(GorcCmd::Keys(keys)) if keys.is_menu()),
+            Err(_) => false,
+        };
+        if !opens_menu {
+            args.pop();
+        }
+        args
+    }
+
+    /// Configuration file to load: `--config`, or `gorc.toml` in the working
+    /// directory if there is one
+    pub fn config_file(&self) -> Option<PathBuf> {
+        match &self.config {
+            Some(cfg) => Some(cfg.clone()),
+            None => self.command.as_ref().and_then(|cmd| cmd.config_path()),
+        }
+    }
+
+    /// Global command-line overrides of configuration fields
+    pub fn overrides(&self) -> Vec<(&'static str, Value)> {
+        let mut overrides = Vec::new();
+        if let Some(keystore) = &self.keystore {
+            overrides.push(("keystore", Value::String(keystore.clone())));
+        }
+
+        if let Some(backend) = self.keystore_backend {
+            let backend = Value::try_from(backend).expect("Could not serialize keystore backend");
+            overrides.push(("keystore_backend", backend));
+        }
+
+        if let Some(format) = self.keystore_format {
+            let format = Value::try_from(format).expect("Could not serialize keystore format");
+            overrides.push(("keystore_format", format));
+        }
+
+        if self.keystore_readonly {
+            overrides.push(("keystore_readonly", Value::Boolean(true)));
+        }
+
+        overrides
+    }
+}
+
+impl Configurable<GorcConfig> for EntryPoint {
+    /// The file is loaded by `process_config` instead, so that it can be
+    /// layered over the defaults and under environment variables.
+    fn config_path(&self) -> Option<PathBuf> {
+        None
+    }
+
+    /// Load the configuration from the config file, `GORC_*` environment
+    /// variables and global command-line overrides
+    fn process_config(&self, config: GorcConfig) -> Result<GorcConfig, FrameworkError> {
+        // `config validate` loads the configuration itself to report what's
+        // wrong with it instead of failing to start.
+        if let Some(GorcCmd::Config(_)) = &self.command {
+            return Ok(config);
+        }
+
+        let (mut config, fields) =
+            crate::config::load(self.config_file().as_deref(), &self.overrides())
+                .map_err(|err| FrameworkErrorKind::ConfigError.context(err))?;
+        config.sources = fields;
+        Ok(config)
+    }
+}
+
 /// Gorc Subcommands
 #[derive(Command, Debug, Options, Runnable)]
 pub enum GorcCmd {
@@ -54,6 +222,12 @@ pub enum GorcCmd {
 
     #[options(help = "display version information")]
     Version(VersionCmd),
+
+    #[options(help = "generate shell completion scripts")]
+    Completions(CompletionsCmd),
+
+    #[options(help = "configuration file commands")]
+    Config(ConfigCmd),
 }
 
 /// This trait allows you to define how application configuration is loaded.
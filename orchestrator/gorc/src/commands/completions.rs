@@ -0,0 +1,296 @@
+//! `completions` subcommand
+
+use super::{EntryPoint, GorcCmd};
+use crate::prelude::*;
+use abscissa_core::{
+    command::{Command, Usage},
+    Options, Runnable,
+};
+use std::{fmt::Write, process, str::FromStr};
+
+/// Shells completion scripts can be generated for
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            _ => Err(format!("unknown shell '{}', expected bash|zsh|fish", s)),
+        }
+    }
+}
+
+/// `completions` subcommand
+#[derive(Command, Debug, Default, Options)]
+pub struct CompletionsCmd {
+    #[options(free, help = "completions [bash|zsh|fish]")]
+    pub args: Vec<String>,
+}
+
+// Entry point for `gorc completions [bash|zsh|fish]`
+impl Runnable for CompletionsCmd {
+    fn run(&self) {
+        let shell = match self.args.get(0) {
+            Some(shell) => shell.parse(),
+            None => Err("missing shell, expected bash|zsh|fish".to_string()),
+        };
+        let shell = shell.unwrap_or_else(|err| {
+            error!("{}", err);
+            process::exit(1);
+        });
+
+        print!("{}", script(shell));
+    }
+}
+
+/// A command in the tree, identified by its path joined with `_`
+/// (e.g. `gorc_keys_eth`).
+struct Node<'a> {
+    id: String,
+    subcommands: Vec<(&'a str, &'a str)>,
+    flags: Vec<Flag<'a>>,
+}
+
+/// A flag accepted by a command
+struct Flag<'a> {
+    short: Option<char>,
+    long: Option<&'a str>,
+    takes_value: bool,
+    description: Option<&'a str>,
+}
+
+/// Flatten the usage tree into one node per command
+fn collect<'a>(id: String, usage: &'a Usage, nodes: &mut Vec<Node<'a>>) {
+    let subcommands = usage
+        .subcommands
+        .iter()
+        .map(|cmd| (cmd.name.as_str(), cmd.description.as_str()))
+        .collect();
+
+    nodes.push(Node {
+        id: id.clone(),
+        subcommands,
+        flags: usage
+            .flags
+            .iter()
+            .map(|flag| Flag {
+                short: flag.short,
+                long: flag.long.as_deref(),
+                takes_value: flag.long_param.is_some(),
+                description: flag.description.as_deref(),
+            })
+            .collect(),
+    });
+
+    for cmd in &usage.subcommands {
+        if let Some(usage) = &cmd.usage {
+            collect(format!("{}_{}", id, cmd.name), usage, nodes);
+        }
+    }
+}
+
+/// Every spelling of a flag, e.g. `-o` and `--output`
+fn flag_words(flag: &Flag<'_>) -> Vec<String> {
+    let mut words = Vec::new();
+    if let Some(short) = flag.short {
+        words.push(format!("-{}", short));
+    }
+    if let Some(long) = flag.long {
+        words.push(format!("--{}", long));
+    }
+    words
+}
+
+/// Words which may follow the command identified by the node
+fn node_words(node: &Node<'_>) -> Vec<String> {
+    let mut words: Vec<String> = node
+        .subcommands
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .collect();
+    words.extend(node.flags.iter().flat_map(flag_words));
+    words
+}
+
+/// Generate the completion script for the given shell
+pub fn script(shell: Shell) -> String {
+    let name = GorcCmd::name();
+
+    // Global options are only accepted ahead of the first subcommand.
+    let global = Usage::for_command::<EntryPoint>();
+    let mut root = Usage::for_command::<GorcCmd>();
+    root.flags = global.flags;
+
+    let mut nodes = Vec::new();
+    collect(name.to_string(), &root, &mut nodes);
+
+    match shell {
+        Shell::Bash => bash(name, &nodes),
+        Shell::Zsh => zsh(name, &nodes),
+        Shell::Fish => fish(name, &nodes),
+    }
+}
+
+fn bash(name: &str, nodes: &[Node<'_>]) -> String {
+    let ids: Vec<&str> = nodes.iter().map(|node| node.id.as_str()).collect();
+
+    let mut s = String::new();
+    writeln!(s, "_{}() {{", name).unwrap();
+    writeln!(s, "    local cur cmd i opts").unwrap();
+    writeln!(s, "    local commands=\" {} \"", ids.join(" ")).unwrap();
+    writeln!(s, "    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"").unwrap();
+    writeln!(s, "    cmd=\"{}\"", name).unwrap();
+    writeln!(s, "    for ((i = 1; i < COMP_CWORD; i++)); do").unwrap();
+    writeln!(
+        s,
+        "        if [[ \"$commands\" == *\" ${{cmd}}_${{COMP_WORDS[i]}} \"* ]]; then"
+    )
+    .unwrap();
+    writeln!(s, "            cmd=\"${{cmd}}_${{COMP_WORDS[i]}}\"").unwrap();
+    writeln!(s, "        fi").unwrap();
+    writeln!(s, "    done").unwrap();
+    writeln!(s, "    case \"$cmd\" in").unwrap();
+    for node in nodes {
+        writeln!(s, "        {})", node.id).unwrap();
+        writeln!(s, "            opts=\"{}\"", node_words(node).join(" ")).unwrap();
+        writeln!(s, "            ;;").unwrap();
+    }
+    writeln!(s, "        *)").unwrap();
+    writeln!(s, "            opts=\"\"").unwrap();
+    writeln!(s, "            ;;").unwrap();
+    writeln!(s, "    esac").unwrap();
+    writeln!(s, "    COMPREPLY=($(compgen -W \"$opts\" -- \"$cur\"))").unwrap();
+    writeln!(s, "}}").unwrap();
+    writeln!(s).unwrap();
+    writeln!(s, "complete -F _{} -o default {}", name, name).unwrap();
+    s
+}
+
+fn zsh(name: &str, nodes: &[Node<'_>]) -> String {
+    let ids: Vec<&str> = nodes.iter().map(|node| node.id.as_str()).collect();
+
+    let mut s = String::new();
+    writeln!(s, "#compdef {}", name).unwrap();
+    writeln!(s).unwrap();
+    writeln!(s, "_{}() {{", name).unwrap();
+    writeln!(s, "    local cmd={} word", name).unwrap();
+    writeln!(s, "    local -a opts").unwrap();
+    writeln!(s, "    for word in ${{words[2,CURRENT-1]}}; do").unwrap();
+    writeln!(s, "        case \"${{cmd}}_${{word}}\" in").unwrap();
+    writeln!(s, "            {})", ids.join("|")).unwrap();
+    writeln!(s, "                cmd=\"${{cmd}}_${{word}}\"").unwrap();
+    writeln!(s, "                ;;").unwrap();
+    writeln!(s, "        esac").unwrap();
+    writeln!(s, "    done").unwrap();
+    writeln!(s, "    case \"$cmd\" in").unwrap();
+    for node in nodes {
+        writeln!(s, "        {})", node.id).unwrap();
+        writeln!(s, "            opts=({})", node_words(node).join(" ")).unwrap();
+        writeln!(s, "            ;;").unwrap();
+    }
+    writeln!(s, "    esac").unwrap();
+    writeln!(s, "    compadd -a opts || _files").unwrap();
+    writeln!(s, "}}").unwrap();
+    writeln!(s).unwrap();
+    writeln!(s, "if [ \"$funcstack[1]\" = \"_{}\" ]; then", name).unwrap();
+    writeln!(s, "    _{} \"$@\"", name).unwrap();
+    writeln!(s, "else").unwrap();
+    writeln!(s, "    compdef _{} {}", name, name).unwrap();
+    writeln!(s, "fi").unwrap();
+    s
+}
+
+fn fish(name: &str, nodes: &[Node<'_>]) -> String {
+    let ids: Vec<&str> = nodes.iter().map(|node| node.id.as_str()).collect();
+
+    let mut s = String::new();
+    writeln!(s, "function __{}_command", name).unwrap();
+    writeln!(s, "    set -l cmd {}", name).unwrap();
+    writeln!(s, "    set -l words (commandline -opc)").unwrap();
+    writeln!(s, "    set -e words[1]").unwrap();
+    writeln!(s, "    for word in $words").unwrap();
+    writeln!(
+        s,
+        "        if contains -- \"$cmd\"_\"$word\" {}",
+        ids.join(" ")
+    )
+    .unwrap();
+    writeln!(s, "            set cmd \"$cmd\"_\"$word\"").unwrap();
+    writeln!(s, "        end").unwrap();
+    writeln!(s, "    end").unwrap();
+    writeln!(s, "    test \"$cmd\" = \"$argv[1]\"").unwrap();
+    writeln!(s, "end").unwrap();
+    writeln!(s).unwrap();
+
+    for node in nodes {
+        let condition = format!("-n '__{}_command {}'", name, node.id);
+
+        for (subcommand, description) in &node.subcommands {
+            writeln!(
+                s,
+                "complete -c {} {} -f -a {} -d '{}'",
+                name,
+                condition,
+                subcommand,
+                fish_escape(description)
+            )
+            .unwrap();
+        }
+
+        for flag in &node.flags {
+            write!(s, "complete -c {} {}", name, condition).unwrap();
+            if let Some(short) = flag.short {
+                write!(s, " -s {}", short).unwrap();
+            }
+            if let Some(long) = flag.long {
+                write!(s, " -l {}", long).unwrap();
+            }
+            if flag.takes_value {
+                write!(s, " -r").unwrap();
+            }
+            if let Some(description) = flag.description {
+                write!(s, " -d '{}'", fish_escape(description)).unwrap();
+            }
+            writeln!(s).unwrap();
+        }
+    }
+    s
+}
+
+/// Escape a string for use inside single quotes in fish
+fn fish_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{script, Shell};
+
+    #[test]
+    fn scripts_cover_command_tree() {
+        for shell in &[Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let script = script(*shell);
+            assert!(!script.is_empty());
+            assert!(
+                script.contains("keys"),
+                "{:?} script is missing keys",
+                shell
+            );
+            assert!(
+                script.contains("cosmos"),
+                "{:?} script is missing cosmos",
+                shell
+            );
+            assert!(script.contains("gorc_keys_eth_add"));
+        }
+    }
+}
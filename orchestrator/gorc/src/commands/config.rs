@@ -0,0 +1,14 @@
+//! `config` subcommand
+
+mod validate;
+
+pub use self::validate::ValidateConfigCmd;
+
+use abscissa_core::{Command, Options, Runnable};
+
+/// `config` subcommand
+#[derive(Command, Debug, Options, Runnable)]
+pub enum ConfigCmd {
+    #[options(help = "check the configuration file for problems")]
+    Validate(ValidateConfigCmd),
+}
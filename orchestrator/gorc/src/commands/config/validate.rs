@@ -0,0 +1,224 @@
+use crate::{
+    commands::{
+        keys::{derivation, keystore},
+        CONFIG_FILE,
+    },
+    config::{self, GorcConfig},
+    output,
+    prelude::*,
+};
+use abscissa_core::{Command, Options, Runnable};
+use std::{fs, path::Path, process};
+use toml::Value;
+
+#[derive(Command, Debug, Default, Options)]
+pub struct ValidateConfigCmd {
+    #[options(help = "print help message")]
+    pub help: bool,
+}
+
+/// Outcome of checking one configuration field: what was found on success,
+/// the problem otherwise
+type Check = (&'static str, Result<String, String>);
+
+// Entry point for `gorc config validate`
+//
+// Checks the configuration resolved from the file given with `-c`, or
+// `gorc.toml` in the working directory, `GORC_*` environment variables and
+// global flags. The entry point supplies the file and flags; run on its own
+// the command checks `gorc.toml` and the environment.
+impl Runnable for ValidateConfigCmd {
+    fn run(&self) {
+        let path = Path::new(CONFIG_FILE);
+        self.validate(Some(path).filter(|path| path.exists()), &[]);
+    }
+}
+
+impl ValidateConfigCmd {
+    /// Resolve and check the configuration, printing the effective value
+    /// and source of each field and a line for each check, and exiting
+    /// non-zero if any of them failed.
+    ///
+    /// The file is loaded here rather than by the application so that a
+    /// file which doesn't parse can be reported like any other problem.
+    pub fn validate(&self, path: Option<&Path>, overrides: &[(&str, Value)]) {
+        let file = path.map_or("none".to_string(), |path| path.display().to_string());
+        let (config, fields) = match config::load(path, overrides) {
+            Ok(resolved) => resolved,
+            Err(err) => {
+                println!("error\tconfig\t{}", err);
+                error!("configuration is invalid");
+                process::exit(1);
+            }
+        };
+
+        let values: Vec<(&str, String)> = fields
+            .iter()
+            .map(|field| {
+                let value = format!("{} ({})", field.value, field.source);
+                (field.name.as_str(), value)
+            })
+            .collect();
+        let mut lines = vec![("config", file.as_str())];
+        lines.extend(values.iter().map(|(name, value)| (*name, value.as_str())));
+        output::print_fields(&lines);
+        println!();
+
+        let checks = check(&config);
+        let mut problems = 0;
+        for (field, result) in &checks {
+            match result {
+                Ok(detail) => println!("ok\t{}\t{}", field, detail),
+                Err(problem) => {
+                    println!("error\t{}\t{}", field, problem);
+                    problems += 1;
+                }
+            }
+        }
+
+        if problems > 0 {
+            error!("configuration has {} problems", problems);
+            process::exit(1);
+        }
+        info!("configuration is valid");
+    }
+}
+
+/// Check the values of a parsed configuration
+fn check(config: &GorcConfig) -> Vec<Check> {
+    vec![
+        (
+            "keystore",
+            config.keystore_dir().and_then(|path| check_keystore(&path)),
+        ),
+        (
+            "keystore_encryption",
+            keystore::scrypt_params(&config.keystore_encryption)
+                .map(|_| {
+                    let section = &config.keystore_encryption;
+                    format!("scrypt n={} r={} p={}", section.n, section.r, section.p)
+                })
+                .map_err(|err| err.to_string()),
+        ),
+        (
+            "gravity.contract",
+            config
+                .gravity
+                .contract
+                .parse::<clarity::Address>()
+                .map(|address| address.to_string())
+                .map_err(|_| format!("invalid address '{}'", config.gravity.contract)),
+        ),
+        (
+            "ethereum.key_derivation_path",
+            check_derivation_path(&config.ethereum.key_derivation_path),
+        ),
+        ("ethereum.rpc", check_url(&config.ethereum.rpc)),
+        (
+            "cosmos.key_derivation_path",
+            check_derivation_path(&config.cosmos.key_derivation_path),
+        ),
+        ("cosmos.grpc", check_url(&config.cosmos.grpc)),
+        ("cosmos.prefix", check_prefix(&config.cosmos.prefix)),
+    ]
+}
+
+/// The keystore must be a readable and writable directory, or be creatable
+fn check_keystore(path: &Path) -> Result<String, String> {
+    if !path.exists() {
+        let parent = path
+            .ancestors()
+            .skip(1)
+            .find(|ancestor| ancestor.is_dir())
+            .ok_or_else(|| format!("{} does not exist", path.display()))?;
+        tempfile::tempfile_in(parent).map_err(|err| {
+            format!(
+                "{} does not exist and can't be created: {}",
+                path.display(),
+                err
+            )
+        })?;
+        return Ok(format!(
+            "{} does not exist, it will be created",
+            path.display()
+        ));
+    }
+
+    if !path.is_dir() {
+        return Err(format!("{} is not a directory", path.display()));
+    }
+
+    fs::read_dir(path).map_err(|err| format!("{} is not readable: {}", path.display(), err))?;
+    tempfile::tempfile_in(path)
+        .map_err(|err| format!("{} is not writable: {}", path.display(), err))?;
+
+    Ok(format!("{} is readable and writable", path.display()))
+}
+
+fn check_derivation_path(path: &str) -> Result<String, String> {
+    derivation::derivation_path(path, None, None).map(|path| path.to_string())
+}
+
+/// Endpoints are reached over plain HTTP or TLS
+fn check_url(url: &str) -> Result<String, String> {
+    let parsed =
+        url::Url::parse(url.trim()).map_err(|err| format!("invalid url '{}': {}", url, err))?;
+    match parsed.scheme() {
+        "http" | "https" => Ok(url.trim().to_string()),
+        scheme => Err(format!(
+            "unsupported url scheme '{}', expected http|https",
+            scheme
+        )),
+    }
+}
+
+/// Bech32 human readable parts are lowercase for the addresses gorc prints
+fn check_prefix(prefix: &str) -> Result<String, String> {
+    let prefix = prefix.trim();
+    let valid = !prefix.is_empty()
+        && prefix
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+    if valid {
+        Ok(prefix.to_string())
+    } else {
+        Err(format!("invalid bech32 prefix '{}'", prefix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check;
+    use crate::config::GorcConfig;
+
+    #[test]
+    fn reports_invalid_fields() {
+        let keystore = tempfile::tempdir().unwrap();
+
+        let mut config = GorcConfig {
+            keystore: keystore.path().to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+        assert!(check(&config).iter().all(|(_, result)| result.is_ok()));
+
+        config.gravity.contract = "0x1234".to_string();
+        config.ethereum.rpc = "localhost:8545".to_string();
+        config.cosmos.prefix = "Cosmos".to_string();
+        config.keystore_encryption.n = 1000;
+
+        let failed: Vec<_> = check(&config)
+            .into_iter()
+            .filter(|(_, result)| result.is_err())
+            .map(|(field, _)| field)
+            .collect();
+        assert_eq!(
+            failed,
+            vec![
+                "keystore_encryption",
+                "gravity.contract",
+                "ethereum.rpc",
+                "cosmos.prefix"
+            ]
+        );
+    }
+}
@@ -1,12 +1,30 @@
 //! `keys` subcommand
 
+mod audit;
 mod cosmos;
+pub mod derivation;
 mod eth;
+pub mod keystore;
+mod list;
+mod menu;
+mod mnemonic;
+mod self_test;
 
+use crate::prelude::*;
 use abscissa_core::{Command, Options, Runnable};
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+};
 
+use crate::commands::keys::audit::AuditKeysCmd;
 use crate::commands::keys::cosmos::CosmosKeysCmd;
 use crate::commands::keys::eth::EthKeysCmd;
+use crate::commands::keys::list::ListKeysCmd;
+use crate::commands::keys::menu::MenuCmd;
+use crate::commands::keys::mnemonic::MnemonicCmd;
+use crate::commands::keys::self_test::SelfTestCmd;
 
 /// `keys` subcommand
 ///
@@ -17,9 +35,93 @@ use crate::commands::keys::eth::EthKeysCmd;
 /// <https://docs.rs/gumdrop/>
 #[derive(Command, Debug, Options, Runnable)]
 pub enum KeysCmd {
-    #[options(name = "cosmos")]
-    CosmosKeysCmd(CosmosKeysCmd),
+    #[options(help = "manage cosmos keys")]
+    Cosmos(CosmosKeysCmd),
 
-    #[options(name = "eth")]
-    EthKeysCmd(EthKeysCmd),
+    #[options(help = "manage ethereum keys")]
+    Eth(EthKeysCmd),
+
+    #[options(help = "list eth and cosmos keys together")]
+    List(ListKeysCmd),
+
+    #[options(help = "check the keystore for duplicate and corrupt keys")]
+    Audit(AuditKeysCmd),
+
+    #[options(help = "generate bip39-mnemonics without storing a key")]
+    Mnemonic(MnemonicCmd),
+
+    #[options(help = "check that keys can be generated, stored and used for signing")]
+    SelfTest(SelfTestCmd),
+
+    #[options(help = "choose a key command interactively")]
+    Menu(MenuCmd),
+}
+
+impl KeysCmd {
+    /// Whether this is one of the interactive menus run in place of a
+    /// missing subcommand
+    pub fn is_menu(&self) -> bool {
+        matches!(
+            self,
+            KeysCmd::Menu(_)
+                | KeysCmd::Eth(EthKeysCmd::Menu(_))
+                | KeysCmd::Cosmos(CosmosKeysCmd::Menu(_))
+        )
+    }
+}
+
+/// Ask the user on the terminal to confirm a potentially dangerous action.
+///
+/// Always returns `false` when stdin is not a terminal.
+fn confirm(prompt: &str) -> bool {
+    if !atty::is(atty::Stream::Stdin) {
+        return false;
+    }
+
+    eprint!("{} [y/N]: ", prompt);
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .expect("Could not read confirmation");
+
+    matches!(answer.trim(), "y" | "Y" | "yes")
+}
+
+/// Read a bip39-mnemonic given as an argument, in a file, or on stdin,
+/// prompting for it when stdin is a terminal.
+fn read_mnemonic(arg: Option<&String>, file: &Option<PathBuf>) -> String {
+    read_secret(arg, file, "bip39-mnemonic")
+}
+
+/// Read a secret given as an argument, in a file, or on stdin when the
+/// argument is omitted or `-`.
+///
+/// On a terminal the user is prompted for the secret without echoing it.
+/// Secrets passed as arguments still work but end up in the shell history
+/// and process listings, so they draw a warning.
+fn read_secret(arg: Option<&String>, file: &Option<PathBuf>, description: &str) -> String {
+    match (arg.map(String::as_str), file) {
+        (Some("-"), _) | (None, None) => {
+            if atty::is(atty::Stream::Stdin) {
+                let prompt = format!("> Enter your {}:\n", description);
+                return rpassword::read_password_from_tty(Some(&prompt))
+                    .expect("Could not read secret");
+            }
+
+            let mut secret = String::new();
+            io::stdin()
+                .read_to_string(&mut secret)
+                .expect("Could not read stdin");
+            secret
+        }
+        (Some(secret), _) => {
+            warn!(
+                "passing the {} as an argument is insecure, it can leak through the shell history \
+                 and process listings; omit it or pass '-' to read it from stdin",
+                description
+            );
+            secret.to_string()
+        }
+        (None, Some(file)) => fs::read_to_string(file).expect("Could not read secret file"),
+    }
 }
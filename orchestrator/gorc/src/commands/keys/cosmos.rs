@@ -1,10 +1,13 @@
 mod add;
+mod addr_from_mnemonic;
 mod delete;
+mod export;
 mod import;
 mod list;
 mod rename;
-mod show;
+pub mod show;
 
+use super::menu::CosmosMenuCmd;
 use abscissa_core::{Command, Options, Runnable};
 
 #[derive(Command, Debug, Options, Runnable)]
@@ -15,15 +18,24 @@ pub enum CosmosKeysCmd {
     #[options(help = "import [name] (bip39-mnemnoic)")]
     Import(import::ImportCosmosKeyCmd),
 
-    #[options(help = "delete [name]")]
+    #[options(help = "delete [name]...")]
     Delete(delete::DeleteCosmosKeyCmd),
 
+    #[options(help = "export [name]")]
+    Export(export::ExportCosmosKeyCmd),
+
     #[options(help = "rename [name] [new-name]")]
     Rename(rename::RenameCosmosKeyCmd),
 
-    #[options(help = "list")]
+    #[options(help = "list (pattern)")]
     List(list::ListCosmosKeyCmd),
 
     #[options(help = "show [name]")]
     Show(show::ShowCosmosKeyCmd),
+
+    #[options(help = "addr-from-mnemonic (bip39-mnemonic)")]
+    AddrFromMnemonic(addr_from_mnemonic::AddrFromMnemonicCosmosKeyCmd),
+
+    #[options(help = "choose a command interactively")]
+    Menu(CosmosMenuCmd),
 }
@@ -1,11 +1,17 @@
 use super::show::ShowCosmosKeyCmd;
-use crate::application::APP;
+use crate::{
+    application::APP,
+    commands::keys::{
+        derivation,
+        keystore::{self, KeyMetadata, KeyType},
+    },
+    prelude::*,
+};
 use abscissa_core::{Application, Command, Options, Runnable};
 use bip32;
 use k256::pkcs8::ToPrivateKey;
 use rand_core::OsRng;
-use signatory::FsKeyStore;
-use std::path;
+use std::path::PathBuf;
 
 #[derive(Command, Debug, Default, Options)]
 pub struct AddCosmosKeyCmd {
@@ -14,6 +20,15 @@ pub struct AddCosmosKeyCmd {
 
     #[options(help = "overwrite existing key")]
     pub overwrite: bool,
+
+    #[options(help = "read the keystore password from a file")]
+    pub password_file: Option<PathBuf>,
+
+    #[options(help = "BIP44 account (default: from the configured derivation path)")]
+    pub account: Option<u32>,
+
+    #[options(help = "BIP44 address index (default: from the configured derivation path)")]
+    pub index: Option<u32>,
 }
 
 // `gorc keys cosmos add [name]`
@@ -21,39 +36,37 @@ pub struct AddCosmosKeyCmd {
 impl Runnable for AddCosmosKeyCmd {
     fn run(&self) {
         let config = APP.config();
-        let keystore = path::Path::new(&config.keystore);
-        let keystore = FsKeyStore::create_or_open(keystore).expect("Could not open keystore");
+        keystore::require_writable("keys cosmos add");
 
         let name = self.args.get(0).expect("name is required");
-        let name = name.parse().expect("Could not parse name");
-        if let Ok(_info) = keystore.info(&name) {
-            if !self.overwrite {
-                eprintln!("Key already exists, exiting.");
-                return;
-            }
+        let name = keystore::key_name(name);
+        if keystore::key_exists(&name) && !self.overwrite {
+            warn!("key '{}' already exists, exiting", name);
+            return;
         }
 
+        let path =
+            derivation::resolve(&config.cosmos.key_derivation_path, self.account, self.index);
+
         let mnemonic = bip32::Mnemonic::random(&mut OsRng, Default::default());
         eprintln!("**Important** record this bip39-mnemonic in a safe place:");
         println!("{}", mnemonic.phrase());
 
         let seed = mnemonic.to_seed("");
 
-        let path = config.cosmos.key_derivation_path.clone();
-        let path = path
-            .parse::<bip32::DerivationPath>()
-            .expect("Could not parse derivation path");
-
         let key = bip32::XPrv::derive_from_path(seed, &path).expect("Could not derive key");
         let key = k256::SecretKey::from(key.private_key());
         let key = key
             .to_pkcs8_der()
             .expect("Could not PKCS8 encod private key");
 
-        keystore.store(&name, &key).expect("Could not store key");
+        keystore::store_key(&name, &key, &self.password_file);
+        keystore::write_metadata(
+            &name,
+            &KeyMetadata::new(KeyType::Cosmos, Some(&path.to_string())),
+        )
+        .unwrap_or_else(|err| err.exit());
 
-        let args = vec![name.to_string()];
-        let show_cmd = ShowCosmosKeyCmd { args };
-        show_cmd.run();
+        ShowCosmosKeyCmd::default().show(&name, &key);
     }
 }
@@ -0,0 +1,81 @@
+use super::show;
+use crate::{
+    application::APP,
+    commands::keys::{derivation, read_mnemonic},
+    output::{self, OutputFormat},
+    prelude::*,
+};
+use abscissa_core::{Application, Command, Options, Runnable};
+use serde::Serialize;
+use std::{path::PathBuf, process};
+
+#[derive(Command, Debug, Default, Options)]
+pub struct AddrFromMnemonicCosmosKeyCmd {
+    #[options(free, help = "addr-from-mnemonic (bip39-mnemonic)")]
+    pub args: Vec<String>,
+
+    #[options(help = "read the bip39-mnemonic from a file")]
+    pub mnemonic_file: Option<PathBuf>,
+
+    #[options(help = "BIP44 account (default: from the configured derivation path)")]
+    pub account: Option<u32>,
+
+    #[options(help = "BIP44 address index (default: from the configured derivation path)")]
+    pub index: Option<u32>,
+
+    #[options(
+        no_short,
+        help = "bech32 prefix of the address (default: configured prefix)"
+    )]
+    pub bech32_prefix: Option<String>,
+
+    #[options(short = "o", help = "output format (text|json)")]
+    pub output: OutputFormat,
+}
+
+/// Address a mnemonic derives
+#[derive(Debug, Serialize)]
+pub struct DerivedAddress {
+    pub address: String,
+    pub derivation_path: String,
+}
+
+// Entry point for `gorc keys cosmos addr-from-mnemonic (bip39-mnemonic)`
+// - (bip39-mnemonic) optional; when absent it is read from --mnemonic-file or
+//   stdin, or the user will be prompted to enter it
+//
+// Nothing is written to the keystore.
+impl Runnable for AddrFromMnemonicCosmosKeyCmd {
+    fn run(&self) {
+        let config = APP.config();
+
+        let mnemonic = read_mnemonic(self.args.get(0), &self.mnemonic_file);
+        let path =
+            derivation::resolve(&config.cosmos.key_derivation_path, self.account, self.index);
+        let key = derivation::derive_key(&mnemonic, &path).unwrap_or_else(|err| {
+            error!("{}", err);
+            process::exit(1);
+        });
+
+        let prefix = self
+            .bech32_prefix
+            .as_deref()
+            .unwrap_or(&config.cosmos.prefix);
+        let address = show::address(&key, prefix.trim()).unwrap_or_else(|err| {
+            error!("{}", err);
+            process::exit(1);
+        });
+        let derived = DerivedAddress {
+            address,
+            derivation_path: path.to_string(),
+        };
+
+        match self.output {
+            OutputFormat::Text => output::print_fields(&[
+                ("address", &derived.address),
+                ("derivation_path", &derived.derivation_path),
+            ]),
+            OutputFormat::Json => output::print_json(&derived),
+        }
+    }
+}
@@ -1,25 +1,96 @@
-use crate::application::APP;
+use super::show::ShowCosmosKeyCmd;
+use crate::{
+    application::APP,
+    commands::keys::{confirm, keystore},
+    prelude::*,
+};
 use abscissa_core::{Application, Command, Options, Runnable};
-use signatory::FsKeyStore;
-use std::path::Path;
+use std::{path::PathBuf, process};
 
 #[derive(Command, Debug, Default, Options)]
 pub struct DeleteCosmosKeyCmd {
-    #[options(free, help = "delete [name]")]
+    #[options(free, help = "delete [name]...")]
     pub args: Vec<String>,
+
+    #[options(help = "read the keystore password from a file")]
+    pub password_file: Option<PathBuf>,
+
+    #[options(help = "print the key files that would be deleted without deleting it")]
+    pub dry_run: bool,
+
+    #[options(help = "skip the confirmation prompt")]
+    pub yes: bool,
 }
 
-/// The `gork keys cosmos delete [name] ` subcommand: delete the given key
+/// The `gork keys cosmos delete [name]...` subcommand: delete the given keys,
+/// reporting each one that can't be deleted and carrying on with the rest
 impl Runnable for DeleteCosmosKeyCmd {
     fn run(&self) {
         let config = APP.config();
-        // Path where key is stored.
-        let keystore = Path::new(&config.keystore);
-        let keystore = signatory::FsKeyStore::create_or_open(keystore).unwrap();
-        // Collect key name from args.
-        let name = self.args.get(0).expect("name is required");
-        let name = name.parse().expect("Could not parse name");
+        if self.args.is_empty() {
+            error!("at least one key name is required");
+            process::exit(1);
+        }
+        if !self.dry_run {
+            keystore::require_writable("keys cosmos delete");
+        }
+
+        let prefix = config.cosmos.prefix.trim();
+        let mut password = keystore::SharedPassword::new(&self.password_file);
+        let mut failed = Vec::new();
+        for name in &self.args {
+            if let Err(err) = self.delete(name, prefix, &mut password) {
+                error!("could not delete key '{}': {}", name, err);
+                failed.push(name.as_str());
+            }
+        }
+        if !failed.is_empty() {
+            error!(
+                "{} of {} keys not deleted: {}",
+                failed.len(),
+                self.args.len(),
+                failed.join(", ")
+            );
+            process::exit(1);
+        }
+    }
+}
+
+impl DeleteCosmosKeyCmd {
+    fn delete(
+        &self,
+        name: &str,
+        prefix: &str,
+        password: &mut keystore::SharedPassword<'_>,
+    ) -> Result<(), String> {
+        let name = keystore::validate_key_name(name).map_err(|err| err.to_string())?;
+        if !keystore::key_exists(&name) {
+            return Err("key does not exist".to_string());
+        }
+
+        // Show which key is about to go before anything is removed; a key
+        // that can't be loaded can still be deleted by its path.
+        let location = keystore::key_location(&name);
+        let info = password
+            .load_key(&name)
+            .and_then(|key| ShowCosmosKeyCmd::info(&name, &key, prefix));
+        let address = match info {
+            Ok(info) => info.address,
+            Err(err) => format!("address unavailable: {}", err),
+        };
+        if self.dry_run {
+            println!("would delete {}\t{}", location, address);
+            return Ok(());
+        }
+
+        let prompt = format!("Delete key '{}' ({}) at {}?", name, address, location);
+        if !self.yes && !confirm(&prompt) {
+            return Err("not confirmed (pass --yes)".to_string());
+        }
+
         // Delete keyname after locating file from path and key name.
-        let _delete_key = FsKeyStore::delete(&keystore, &name).unwrap();
+        keystore::delete_key(&name).map_err(|err| err.to_string())?;
+        info!("deleted key '{}'", name);
+        Ok(())
     }
 }
@@ -0,0 +1,72 @@
+use crate::{
+    commands::keys::{confirm, keystore},
+    prelude::*,
+};
+use abscissa_core::{Command, Options, Runnable};
+use std::{path::PathBuf, process};
+
+#[derive(Command, Debug, Default, Options)]
+pub struct ExportCosmosKeyCmd {
+    #[options(free, help = "export [name]")]
+    pub args: Vec<String>,
+
+    #[options(no_short, help = "write the exported key to a file instead of stdout")]
+    pub out_file: Option<PathBuf>,
+
+    #[options(no_short, help = "export the raw private key as unencrypted hex")]
+    pub unsafe_unarmored_hex: bool,
+
+    #[options(help = "skip the confirmation prompt for unencrypted exports")]
+    pub yes: bool,
+
+    #[options(help = "read the keystore password from a file")]
+    pub password_file: Option<PathBuf>,
+}
+
+// Entry point for `gorc keys cosmos export [name]`
+// - [name] required; key name
+//
+// Keys are exported as encrypted PKCS#8 PEM. Keys stored unencrypted are
+// armored with a password for the export.
+impl Runnable for ExportCosmosKeyCmd {
+    fn run(&self) {
+        let name = self.args.get(0).expect("name is required");
+        let name = keystore::key_name(name);
+
+        if !keystore::key_exists(&name) {
+            error!("key '{}' does not exist", name);
+            process::exit(1);
+        }
+
+        let exported = if self.unsafe_unarmored_hex {
+            if !self.yes && !confirm("Export the unencrypted private key?") {
+                error!("refusing to export unencrypted key without confirmation (pass --yes)");
+                process::exit(1);
+            }
+
+            let key = keystore::load_key(&name, &self.password_file);
+            let key = keystore::secret_key(&key).expect("Could not parse key");
+            format!("{}\n", deep_space::utils::bytes_to_hex_str(&key.to_bytes()))
+        } else {
+            let stored = keystore::stored_key(&name).expect("Could not load key");
+            if keystore::is_encrypted(&stored) {
+                stored
+            } else {
+                let key = keystore::load_key(&name, &self.password_file);
+                let password = keystore::export_password(&self.password_file);
+                keystore::encrypt_key(&key, &password).to_pem().to_string()
+            }
+        };
+
+        match &self.out_file {
+            Some(path) => {
+                if let Err(err) = keystore::write_secret_file(path, exported.as_bytes()) {
+                    error!("could not write {}: {}", path.display(), err);
+                    process::exit(1);
+                }
+                info!("exported key '{}' to {}", name, path.display());
+            }
+            None => print!("{}", exported),
+        }
+    }
+}
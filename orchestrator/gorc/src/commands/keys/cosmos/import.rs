@@ -1,10 +1,22 @@
-use super::show::ShowCosmosKeyCmd;
-use crate::application::APP;
+use super::show::{self, ShowCosmosKeyCmd};
+use crate::{
+    application::APP,
+    commands::keys::{
+        derivation,
+        keystore::{self, sdk_keyring, KeyMetadata, KeyName, KeyType},
+        read_mnemonic, read_secret,
+    },
+    prelude::*,
+};
 use abscissa_core::{Application, Command, Options, Runnable};
 use bip32;
 use k256::pkcs8::ToPrivateKey;
-use signatory::FsKeyStore;
-use std::path;
+use pkcs8::PrivateKeyDocument;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
 
 #[derive(Command, Debug, Default, Options)]
 pub struct ImportCosmosKeyCmd {
@@ -13,52 +25,266 @@ pub struct ImportCosmosKeyCmd {
 
     #[options(help = "overwrite existing key")]
     pub overwrite: bool,
+
+    #[options(help = "read the keystore password from a file")]
+    pub password_file: Option<PathBuf>,
+
+    #[options(help = "import keys from a file with one name:bip39-mnemonic per line")]
+    pub batch: Option<PathBuf>,
+
+    #[options(help = "BIP44 account (default: from the configured derivation path)")]
+    pub account: Option<u32>,
+
+    #[options(help = "BIP44 address index (default: from the configured derivation path)")]
+    pub index: Option<u32>,
+
+    #[options(help = "import the key from a Cosmos SDK keyring-file directory")]
+    pub from_keyring_dir: Option<PathBuf>,
+
+    #[options(help = "name of the key in the keyring (default: [name])")]
+    pub name: Option<String>,
+
+    #[options(no_short, help = "read the keyring password from a file")]
+    pub keyring_password_file: Option<PathBuf>,
 }
 
 // `gorc keys cosmos import [name] (bip39-mnemonic)`
 // - [name] required; key name
-// - (bip39-mnemonic) optional; when absent the user will be prompted to enter it
+// - (bip39-mnemonic) optional; when absent or `-` it is read from stdin, or the
+//   user will be prompted to enter it
+//
+// `gorc keys cosmos import --batch [file]`
+// - [file] required; lines of name:bip39-mnemonic, blank lines and lines
+//   starting with '#' are ignored
+//
+// `gorc keys cosmos import [name] --from-keyring-dir [dir]`
+// - [name] required; key name
+// - [dir] required; `keyring-file` directory of a Cosmos SDK node, or the
+//   node's home directory
 impl Runnable for ImportCosmosKeyCmd {
     fn run(&self) {
+        keystore::require_writable("keys cosmos import");
+        if let Some(batch) = &self.batch {
+            self.import_batch(batch);
+            return;
+        }
+        if let Some(dir) = &self.from_keyring_dir {
+            self.import_from_keyring(dir);
+            return;
+        }
+
         let config = APP.config();
-        let keystore = path::Path::new(&config.keystore);
-        let keystore = FsKeyStore::create_or_open(keystore).expect("Could not open keystore");
+        let name = self.args.get(0).expect("name is required");
+        let name = keystore::key_name(name);
+        if keystore::key_exists(&name) && !self.overwrite {
+            warn!("key '{}' already exists, exiting", name);
+            return;
+        }
 
+        let mnemonic = read_mnemonic(self.args.get(1), &None);
+
+        let path =
+            derivation::resolve(&config.cosmos.key_derivation_path, self.account, self.index);
+        let key = derive_key(&mnemonic, &path).unwrap_or_else(|err| {
+            error!("{}", err);
+            process::exit(1);
+        });
+
+        keystore::store_key(&name, &key, &self.password_file);
+        let metadata = KeyMetadata::new(KeyType::Cosmos, Some(&path.to_string()));
+        keystore::write_metadata(&name, &metadata).unwrap_or_else(|err| err.exit());
+
+        ShowCosmosKeyCmd::default().show(&name, &key);
+    }
+}
+
+impl ImportCosmosKeyCmd {
+    /// Import a key from a Cosmos SDK keyring-file directory, checking its
+    /// address against the one the SDK reports for it
+    fn import_from_keyring(&self, dir: &Path) {
+        let config = APP.config();
         let name = self.args.get(0).expect("name is required");
-        let name = name.parse().expect("Could not parse name");
-        if let Ok(_info) = keystore.info(&name) {
-            if !self.overwrite {
-                eprintln!("Key already exists, exiting.");
-                return;
+        let name = keystore::key_name(name);
+        if keystore::key_exists(&name) && !self.overwrite {
+            warn!("key '{}' already exists, exiting", name);
+            return;
+        }
+
+        let keyring_name = self.name.as_deref().unwrap_or(&name);
+        let password = read_secret(None, &self.keyring_password_file, "keyring password");
+        let password = password
+            .strip_suffix('\n')
+            .map(|p| p.strip_suffix('\r').unwrap_or(p))
+            .unwrap_or(&password);
+
+        let imported = sdk_keyring::read_key(dir, keyring_name, password).unwrap_or_else(|err| {
+            error!(
+                "could not import key '{}' from {}: {}",
+                keyring_name,
+                dir.display(),
+                err
+            );
+            process::exit(1);
+        });
+
+        // The SDK reports the address of the public key stored with the
+        // private key, so the two must agree.
+        let prefix = config.cosmos.prefix.trim();
+        let address = show::address(&imported.key, prefix).unwrap_or_else(|err| {
+            error!("{}", err);
+            process::exit(1);
+        });
+        let reported = deep_space::PublicKey::from_slice(&imported.public_key, prefix)
+            .ok()
+            .and_then(|key| key.to_address_with_prefix(prefix).ok())
+            .map_or_else(|| "an invalid public key".to_string(), |a| a.to_string());
+        if address != reported {
+            error!(
+                "key '{}' in the keyring is for {} but its private key is for {}",
+                keyring_name, reported, address
+            );
+            process::exit(1);
+        }
+
+        let key = imported
+            .key
+            .to_pkcs8_der()
+            .expect("Could not PKCS8 encode private key");
+        keystore::store_key(&name, &key, &self.password_file);
+        keystore::write_metadata(&name, &KeyMetadata::new(KeyType::Cosmos, None))
+            .unwrap_or_else(|err| err.exit());
+
+        ShowCosmosKeyCmd::default().show(&name, &key);
+    }
+
+    /// Import every key listed in the batch file, reporting each line's
+    /// outcome and exiting non-zero if any of them failed.
+    fn import_batch(&self, batch: &Path) {
+        let config = APP.config();
+        let path =
+            derivation::resolve(&config.cosmos.key_derivation_path, self.account, self.index);
+
+        let contents = fs::read_to_string(batch).unwrap_or_else(|err| {
+            error!("could not read batch file {}: {}", batch.display(), err);
+            process::exit(1);
+        });
+
+        // Ask for the password once rather than for every key.
+        let password = keystore::store_password(&self.password_file);
+        let mut total = 0;
+        let mut failed = 0;
+        for (line, entry) in parse_batch(&contents) {
+            total += 1;
+
+            let result = entry.and_then(|(name, mnemonic)| {
+                let imported = self.import_key(name, mnemonic, &path, password.as_deref())?;
+                Ok((name, imported))
+            });
+
+            match result {
+                Ok((_, Some((name, key)))) => ShowCosmosKeyCmd::default().show(&name, &key),
+                Ok((name, None)) => {
+                    warn!("line {}: key '{}' already exists, skipping", line, name);
+                }
+                Err(err) => {
+                    error!("line {}: {}", line, err);
+                    failed += 1;
+                }
             }
         }
 
-        let mnemonic = match self.args.get(1) {
-            Some(mnemonic) => mnemonic.clone(),
-            None => rpassword::read_password_from_tty(Some("> Enter your bip39-mnemonic:\n"))
-                .expect("Could not read mnemonic"),
-        };
+        if failed > 0 {
+            error!("{} of {} keys failed to import", failed, total);
+            process::exit(1);
+        }
+    }
+
+    /// Store a single key, returning `None` if it was skipped because a key
+    /// with the same name already exists.
+    fn import_key(
+        &self,
+        name: &str,
+        mnemonic: &str,
+        derivation_path: &bip32::DerivationPath,
+        password: Option<&str>,
+    ) -> Result<Option<(KeyName, PrivateKeyDocument)>, String> {
+        let name = keystore::validate_key_name(name).map_err(|err| err.to_string())?;
+        if keystore::key_exists(&name) && !self.overwrite {
+            return Ok(None);
+        }
 
-        let mnemonic = bip32::Mnemonic::new(mnemonic.trim(), Default::default())
-            .expect("Could not parse mnemonic");
+        let key = derive_key(mnemonic, derivation_path)?;
+        keystore::try_store_key(&name, &key, password)
+            .map_err(|err| format!("could not store key '{}': {}", name, err))?;
+        let metadata = KeyMetadata::new(KeyType::Cosmos, Some(&derivation_path.to_string()));
+        keystore::write_metadata(&name, &metadata).map_err(|err| err.to_string())?;
+        Ok(Some((name, key)))
+    }
+}
 
-        let seed = mnemonic.to_seed("");
+/// Derive the PKCS#8 encoded key for the mnemonic at the given path
+fn derive_key(
+    mnemonic: &str,
+    derivation_path: &bip32::DerivationPath,
+) -> Result<PrivateKeyDocument, String> {
+    let key = derivation::derive_key(mnemonic, derivation_path)?;
+    let key = key
+        .to_pkcs8_der()
+        .expect("Could not PKCS8 encod private key");
+    Ok(key)
+}
 
-        let path = config.cosmos.key_derivation_path.clone();
-        let path = path
-            .parse::<bip32::DerivationPath>()
-            .expect("Could not parse derivation path");
+/// A `name:mnemonic` pair from a batch file, or why the line is malformed
+type BatchEntry<'a> = Result<(&'a str, &'a str), String>;
 
-        let key = bip32::XPrv::derive_from_path(seed, &path).expect("Could not derive key");
-        let key = k256::SecretKey::from(key.private_key());
-        let key = key
-            .to_pkcs8_der()
-            .expect("Could not PKCS8 encod private key");
+/// Split a batch file into `(line number, entry)` pairs
+fn parse_batch(contents: &str) -> Vec<(usize, BatchEntry<'_>)> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| {
+            let entry = line
+                .split_once(':')
+                .map(|(name, mnemonic)| (name.trim(), mnemonic.trim()))
+                .ok_or_else(|| "expected name:bip39-mnemonic".to_string());
+            (number, entry)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{derive_key, parse_batch};
+    use crate::commands::keys::derivation::derivation_path;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon \
+                            abandon abandon abandon abandon abandon abandon abandon abandon \
+                            abandon abandon abandon abandon abandon abandon abandon art";
+
+    #[test]
+    fn parses_batch_lines() {
+        let contents = "# validators\nvalidator1: abandon abandon about\n\nbroken line\n";
+        let entries = parse_batch(contents);
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], (2, Ok(("validator1", "abandon abandon about"))));
+        assert_eq!(entries[1].0, 4);
+        assert!(entries[1].1.is_err());
+    }
 
-        keystore.store(&name, &key).expect("Could not store key");
+    #[test]
+    fn index_selects_reproducible_key() {
+        let base = "m/44'/118'/0'/0/0";
+        let first = derivation_path(base, None, None).unwrap();
+        let second = derivation_path(base, None, Some(1)).unwrap();
 
-        let args = vec![name.to_string()];
-        let show_cmd = ShowCosmosKeyCmd { args };
-        show_cmd.run();
+        let key = derive_key(MNEMONIC, &second).unwrap();
+        assert_ne!(derive_key(MNEMONIC, &first).unwrap().as_ref(), key.as_ref());
+        assert_eq!(
+            derive_key(MNEMONIC, &second).unwrap().as_ref(),
+            key.as_ref()
+        );
     }
 }
@@ -1,30 +1,129 @@
 use super::show::ShowCosmosKeyCmd;
-use crate::application::APP;
+use crate::{
+    application::APP,
+    commands::keys::{
+        keystore::{self, KeyType},
+        list::{or_dash, ListedKey, SortBy, LOCKED},
+    },
+    error::KeysError,
+    output,
+    prelude::*,
+};
 use abscissa_core::{Application, Command, Options, Runnable};
-use std::path::Path;
+use std::path::PathBuf;
 
 #[derive(Command, Debug, Default, Options)]
-pub struct ListCosmosKeyCmd {}
+pub struct ListCosmosKeyCmd {
+    #[options(free, help = "list (pattern)")]
+    pub args: Vec<String>,
 
-// Entry point for `gorc keys cosmos list`
+    #[options(help = "only list keys whose name starts with this prefix")]
+    pub prefix: Option<String>,
+
+    #[options(no_short, help = "only print the number of keys")]
+    pub count: bool,
+
+    #[options(no_short, help = "order of the keys (name|mtime)")]
+    pub sort_by: SortBy,
+
+    #[options(no_short, help = "also print each key's public key fingerprint")]
+    pub fingerprint: bool,
+
+    #[options(help = "also list keys namespaced in subdirectories of the keystore")]
+    pub recursive: bool,
+
+    #[options(help = "read the keystore password from a file")]
+    pub password_file: Option<PathBuf>,
+}
+
+// Entry point for `gorc keys cosmos list (pattern)`
+// - (pattern) optional; glob the key names must match, e.g. 'validator*'
 impl Runnable for ListCosmosKeyCmd {
     fn run(&self) {
+        self.try_run().unwrap_or_else(|err| err.exit());
+    }
+}
+
+impl ListCosmosKeyCmd {
+    fn try_run(&self) -> Result<(), KeysError> {
         let config = APP.config();
-        let keystore = Path::new(&config.keystore);
-
-        for entry in keystore.read_dir().expect("Could not read keystore") {
-            let path = entry.unwrap().path();
-            if path.is_file() {
-                if let Some(extension) = path.extension() {
-                    if extension == "pem" {
-                        let name = path.file_stem().unwrap();
-                        let name = name.to_str().unwrap();
-                        let args = vec![name.to_string()];
-                        let show_cmd = ShowCosmosKeyCmd { args };
-                        show_cmd.run();
+        let pattern = self.args.get(0).map(String::as_str);
+
+        let mut password = keystore::SharedPassword::new(&self.password_file);
+        let mut keys = Vec::new();
+        let mut failed = 0;
+        for name in keystore::names(self.recursive)? {
+            let name = match name {
+                Ok(name) => name,
+                Err(err) => {
+                    warn!("skipping: {}", err);
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            if !keystore::name_matches(&name, pattern, self.prefix.as_deref()) {
+                continue;
+            }
+            match keystore::has_type(&name, KeyType::Cosmos) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(err) => {
+                    warn!("skipping: {}", err);
+                    failed += 1;
+                    continue;
+                }
+            }
+
+            // A broken or locked key file shouldn't hide the healthy keys.
+            let info = password
+                .load_key(&name)
+                .and_then(|key| ShowCosmosKeyCmd::info(&name, &key, config.cosmos.prefix.trim()))
+                .map(|info| (info, false))
+                .or_else(|err| match err {
+                    KeysError::NoPassword { .. } => {
+                        ShowCosmosKeyCmd::locked_info(&name).map(|info| (info, true))
                     }
+                    err => Err(err),
+                });
+            match info {
+                Ok((info, locked)) => keys.push(ListedKey {
+                    info,
+                    modified: keystore::key_modified(&name),
+                    locked,
+                }),
+                Err(err) => {
+                    warn!("skipping: {}", err);
+                    failed += 1;
                 }
             }
         }
+
+        self.sort_by.sort(&mut keys, |key| key.modified.as_deref());
+
+        if self.count {
+            println!("{}", keys.len());
+        } else {
+            let rows: Vec<Vec<&str>> = keys
+                .iter()
+                .map(|key| {
+                    let mut row = vec![key.info.name.as_str(), or_dash(&key.info.address)];
+                    if self.fingerprint {
+                        row.push(or_dash(&key.info.fingerprint));
+                    }
+                    row.push(key.modified.as_deref().unwrap_or("-"));
+                    if key.locked {
+                        row.push(LOCKED);
+                    }
+                    row
+                })
+                .collect();
+            output::print_table(&rows);
+        }
+
+        if failed > 0 {
+            return Err(KeysError::Skipped(failed));
+        }
+        Ok(())
     }
 }
@@ -1,6 +1,6 @@
-use abscissa_core::{Application, Command, Options, Runnable};
-use crate::application::APP;
-use std::path;
+use crate::{commands::keys::keystore, error::KeysError, prelude::*};
+use abscissa_core::{Command, Options, Runnable};
+use std::process;
 
 #[derive(Command, Debug, Default, Options)]
 pub struct RenameCosmosKeyCmd {
@@ -14,24 +14,54 @@ pub struct RenameCosmosKeyCmd {
 /// The `gorc keys cosmos rename [name] [new-name]` subcommand: show keys
 impl Runnable for RenameCosmosKeyCmd {
     fn run(&self) {
-        let config = APP.config();
-        let keystore = path::Path::new(&config.keystore);
-        let keystore = signatory::FsKeyStore::create_or_open(keystore).unwrap();
-
+        keystore::require_writable("keys cosmos rename");
         let name = self.args.get(0).expect("name is required");
-        let name = name.parse().expect("Could not parse name");
+        let name = keystore::key_name(name);
 
         let new_name = self.args.get(1).expect("new_name is required");
-        let new_name = new_name.parse().expect("Could not parse new_name");
-        if let Ok(_info) = keystore.info(&new_name) {
-            if !self.overwrite {
-                println!("Key already exists, exiting.");
-                return;
-            }
+        let new_name = keystore::key_name(new_name);
+
+        // Storing the key under its own name and then deleting the source
+        // would leave no copy of it.
+        if new_name == name {
+            error!("key '{}' can't be renamed to itself", name);
+            process::exit(1);
+        }
+
+        if !keystore::key_exists(&name) {
+            error!("key '{}' does not exist", name);
+            process::exit(1);
         }
 
-        let key = keystore.load(&name).expect("Could not load key");
-        keystore.store(&new_name, &key).unwrap();
-        keystore.delete(&name).unwrap();
+        if keystore::key_exists(&new_name) && !self.overwrite {
+            error!("key '{}' already exists", new_name);
+            process::exit(1);
+        }
+
+        // The stored contents are moved as they are, encrypted or not.
+        let backend = keystore::backend();
+        let key = backend.load(&name).unwrap_or_else(|source| {
+            KeysError::LoadKey {
+                name: name.to_string(),
+                source,
+            }
+            .exit()
+        });
+        if let Err(err) = backend.store(&new_name, &key) {
+            error!(
+                "could not store key '{}', '{}' is unchanged: {}",
+                new_name, name, err
+            );
+            process::exit(1);
+        }
+        if let Err(err) = backend.delete(&name) {
+            error!(
+                "copied key '{}' to '{}' but could not delete it: {}",
+                name, new_name, err
+            );
+            process::exit(1);
+        }
+        keystore::rename_metadata(&name, &new_name).unwrap_or_else(|err| err.exit());
+        info!("renamed key '{}' to '{}'", name, new_name);
     }
 }
@@ -1,39 +1,250 @@
-use crate::application::APP;
+use crate::{
+    application::APP,
+    commands::keys::keystore::{self, KeyName},
+    error::{Error, ErrorKind, KeysError},
+    output::{self, OutputFormat},
+    prelude::*,
+};
 use abscissa_core::{Application, Command, Options, Runnable};
 use deep_space;
-use signatory::FsKeyStore;
-use std::path::Path;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use pkcs8::PrivateKeyDocument;
+use serde::Serialize;
+use std::{path::PathBuf, process, str::FromStr};
 
 #[derive(Command, Debug, Default, Options)]
 pub struct ShowCosmosKeyCmd {
     #[options(free, help = "delete [name]")]
     pub args: Vec<String>,
+
+    #[options(
+        no_short,
+        help = "bech32 prefix of the address (default: configured prefix)"
+    )]
+    pub bech32_prefix: Option<String>,
+
+    #[options(no_short, help = "address to show (acc|valoper|valcons)")]
+    pub address_type: AddressType,
+
+    #[options(short = "o", help = "output format (text|json)")]
+    pub output: OutputFormat,
+
+    #[options(help = "read the keystore password from a file")]
+    pub password_file: Option<PathBuf>,
+}
+
+/// Which of a validator's addresses to show
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AddressType {
+    /// Account address, with the base prefix
+    Acc,
+    /// Validator operator address, `<prefix>valoper`
+    Valoper,
+    /// Validator consensus address, `<prefix>valcons`
+    Valcons,
+}
+
+impl Default for AddressType {
+    fn default() -> Self {
+        AddressType::Acc
+    }
+}
+
+impl FromStr for AddressType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "acc" => Ok(AddressType::Acc),
+            "valoper" => Ok(AddressType::Valoper),
+            "valcons" => Ok(AddressType::Valcons),
+            _ => Err(format!(
+                "unknown address type '{}', expected acc|valoper|valcons",
+                s
+            )),
+        }
+    }
+}
+
+impl AddressType {
+    /// Bech32 prefix of this type of address for the base account prefix.
+    ///
+    /// Consensus addresses belong to the validator's ed25519 consensus key
+    /// rather than a secp256k1 key from the keystore, so they can't be
+    /// derived here.
+    pub fn prefix(self, base: &str) -> Result<String, String> {
+        match self {
+            AddressType::Acc => Ok(base.to_string()),
+            AddressType::Valoper => Ok(format!("{}valoper", base)),
+            AddressType::Valcons => Err(
+                "valcons addresses are derived from the validator's ed25519 consensus key \
+                 (priv_validator_key.json), not a secp256k1 cosmos key"
+                    .to_string(),
+            ),
+        }
+    }
+}
+
+/// Details of a stored Cosmos key
+#[derive(Debug, Serialize)]
+pub struct CosmosKeyInfo {
+    pub name: String,
+    /// Empty for locked keys
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub address: String,
+    /// Compressed SEC1 public key, hex, empty for locked keys
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub public_key: String,
+    /// Fingerprint of the public key, see [`keystore::fingerprint`], empty
+    /// for locked keys
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub fingerprint: String,
+    /// BIP44 path the key was derived along, `None` if it wasn't HD-derived
+    pub derivation_path: Option<String>,
+}
+
+impl ShowCosmosKeyCmd {
+    /// Details of the named key, already loaded from the keystore
+    pub fn info(
+        name: &KeyName,
+        key: &PrivateKeyDocument,
+        prefix: &str,
+    ) -> Result<CosmosKeyInfo, KeysError> {
+        let key = keystore::secret_key(key).map_err(|source| KeysError::LoadKey {
+            name: name.to_string(),
+            source,
+        })?;
+        let public_key = key.public_key().to_encoded_point(true);
+
+        Ok(CosmosKeyInfo {
+            name: name.to_string(),
+            address: address(&key, prefix)?,
+            public_key: deep_space::utils::bytes_to_hex_str(public_key.as_bytes()),
+            fingerprint: keystore::fingerprint(&key),
+            derivation_path: keystore::read_metadata(name)?.derivation_path,
+        })
+    }
+
+    /// What is known of an encrypted key without its password: its
+    /// metadata
+    pub fn locked_info(name: &KeyName) -> Result<CosmosKeyInfo, KeysError> {
+        Ok(CosmosKeyInfo {
+            name: name.to_string(),
+            address: String::new(),
+            public_key: String::new(),
+            fingerprint: String::new(),
+            derivation_path: keystore::read_metadata(name)?.derivation_path,
+        })
+    }
 }
 
 // Entry point for `gorc keys cosmos show [name]`
 impl Runnable for ShowCosmosKeyCmd {
     fn run(&self) {
-        let config = APP.config();
-        let keystore = Path::new(&config.keystore);
-        let keystore = FsKeyStore::create_or_open(keystore).unwrap();
         let name = self.args.get(0).expect("name is required");
-        let name = name.parse().expect("Could not parse name");
+        let name = keystore::key_name(name);
+
+        let key =
+            keystore::try_load_key(&name, &self.password_file).unwrap_or_else(|err| err.exit());
+        self.show(&name, &key);
+    }
+}
+
+impl ShowCosmosKeyCmd {
+    /// Print the details of the named key, already loaded from the keystore
+    pub fn show(&self, name: &KeyName, key: &PrivateKeyDocument) {
+        let config = APP.config();
+        let prefix = self
+            .bech32_prefix
+            .as_deref()
+            .unwrap_or(&config.cosmos.prefix);
+        let prefix = self
+            .address_type
+            .prefix(prefix.trim())
+            .unwrap_or_else(|err| {
+                error!("{}", err);
+                process::exit(1);
+            });
+        let info = Self::info(name, key, &prefix).unwrap_or_else(|err| err.exit());
+
+        match self.output {
+            OutputFormat::Text => output::print_fields(&[
+                ("name", &info.name),
+                ("address", &info.address),
+                ("public_key", &info.public_key),
+                ("fingerprint", &info.fingerprint),
+                (
+                    "derivation_path",
+                    info.derivation_path.as_deref().unwrap_or("none"),
+                ),
+            ]),
+            OutputFormat::Json => output::print_versioned_json(&info),
+        }
+    }
+}
 
-        let key = keystore.load(&name).expect("Could not load key");
-        let key = key
-            .to_pem()
-            .parse::<k256::elliptic_curve::SecretKey<k256::Secp256k1>>()
-            .expect("Could not parse key");
+/// Bech32 encode the account address of the key with the given prefix
+pub fn address(key: &k256::SecretKey, prefix: &str) -> Result<String, Error> {
+    let key = deep_space::utils::bytes_to_hex_str(&key.to_bytes());
+    let key = key
+        .parse::<deep_space::private_key::PrivateKey>()
+        .map_err(|err| ErrorKind::Key.context(err))?;
 
-        let key = deep_space::utils::bytes_to_hex_str(&key.to_bytes());
-        let key = key
-            .parse::<deep_space::private_key::PrivateKey>()
-            .expect("Could not parse private key");
+    key.to_address(prefix)
+        .ok()
+        .and_then(|address| address.to_bech32(prefix).ok())
+        .ok_or_else(|| {
+            ErrorKind::Config
+                .context(format!("invalid bech32 prefix '{}'", prefix))
+                .into()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{address, AddressType};
+
+    #[test]
+    fn same_key_with_different_prefixes() {
+        let key = k256::SecretKey::from_bytes(&[1u8; 32]).unwrap();
 
-        let address = key
-            .to_address(config.cosmos.prefix.trim())
-            .expect("Could not generate public key");
+        let cosmos = address(&key, "cosmos").unwrap();
+        let gravity = address(&key, "gravity").unwrap();
+        assert!(cosmos.starts_with("cosmos1"));
+        assert!(gravity.starts_with("gravity1"));
 
-        println!("{}\t{}", name, address)
+        let cosmos = deep_space::Address::from_bech32(cosmos).unwrap();
+        let gravity = deep_space::Address::from_bech32(gravity).unwrap();
+        assert_eq!(cosmos.as_bytes(), gravity.as_bytes());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn rejects_invalid_prefix() {
+        let key = k256::SecretKey::from_bytes(&[1u8; 32]).unwrap();
+        assert!(address(&key, "bad prefix").is_err());
+    }
+
+    #[test]
+    fn encodes_address_types() {
+        let key = k256::SecretKey::from_bytes(&[1u8; 32]).unwrap();
+        let encode = |address_type: &str| {
+            let address_type: AddressType = address_type.parse().unwrap();
+            address_type
+                .prefix("cosmos")
+                .map(|prefix| address(&key, &prefix).unwrap())
+        };
+
+        let acc = encode("acc").unwrap();
+        let valoper = encode("valoper").unwrap();
+        assert!(acc.starts_with("cosmos1"));
+        assert!(valoper.starts_with("cosmosvaloper1"));
+
+        let acc = deep_space::Address::from_bech32(acc).unwrap();
+        let valoper = deep_space::Address::from_bech32(valoper).unwrap();
+        assert_eq!(acc.as_bytes(), valoper.as_bytes());
+
+        assert!(encode("valcons").is_err());
+        assert!("cons".parse::<AddressType>().is_err());
+        assert_eq!(AddressType::default(), AddressType::Acc);
+    }
+}
@@ -0,0 +1,115 @@
+//! BIP44 derivation paths for the `add` and `import` subcommands
+
+use crate::prelude::*;
+use bip32::DerivationPath;
+use std::process;
+
+/// Largest account number, as accounts are hardened derivation steps
+const MAX_ACCOUNT: u32 = (1 << 31) - 1;
+
+/// HD path to derive a new key along.
+///
+/// `base` is the configured `m/purpose'/coin'/account'/change/index` path;
+/// `account` and `index` replace its account and address index if given.
+pub fn derivation_path(
+    base: &str,
+    account: Option<u32>,
+    index: Option<u32>,
+) -> Result<DerivationPath, String> {
+    let base = base.trim();
+    if account.is_none() && index.is_none() {
+        return base
+            .parse()
+            .map_err(|_| format!("invalid derivation path '{}'", base));
+    }
+
+    let mut components: Vec<String> = base.split('/').map(String::from).collect();
+    if components.len() != 6 || components[0] != "m" {
+        return Err(format!(
+            "derivation path '{}' is not of the form m/purpose'/coin'/account'/change/index",
+            base
+        ));
+    }
+
+    if let Some(account) = account {
+        if account > MAX_ACCOUNT {
+            return Err(format!("account must be at most {}", MAX_ACCOUNT));
+        }
+        components[3] = format!("{}'", account);
+    }
+
+    if let Some(index) = index {
+        components[5] = index.to_string();
+    }
+
+    let path = components.join("/");
+    path.parse()
+        .map_err(|_| format!("invalid derivation path '{}'", path))
+}
+
+/// Derive the secp256k1 key for a 12 to 24 word English bip39-mnemonic
+/// along the given path
+pub fn derive_key(mnemonic: &str, path: &DerivationPath) -> Result<k256::SecretKey, String> {
+    let mnemonic: deep_space::Mnemonic = mnemonic
+        .trim()
+        .parse()
+        .map_err(|_| "invalid bip39-mnemonic".to_string())?;
+
+    let seed = mnemonic.to_seed("");
+    let key = bip32::XPrv::derive_from_path(seed, path).expect("Could not derive key");
+    Ok(k256::SecretKey::from(key.private_key()))
+}
+
+/// Resolve the HD path for a new key.
+///
+/// Exits with an error message if the path is invalid.
+pub fn resolve(base: &str, account: Option<u32>, index: Option<u32>) -> DerivationPath {
+    derivation_path(base, account, index).unwrap_or_else(|err| {
+        error!("{}", err);
+        process::exit(1);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{derivation_path, derive_key};
+
+    const ETH_PATH: &str = "m/44'/60'/0'/0/0";
+
+    #[test]
+    fn keeps_configured_path_by_default() {
+        let path = derivation_path("m/44'/60'/2'/0/7", None, None).unwrap();
+        assert_eq!(path.to_string(), "m/44'/60'/2'/0/7");
+    }
+
+    #[test]
+    fn applies_account_and_index() {
+        let path = derivation_path(ETH_PATH, Some(3), Some(1)).unwrap();
+        assert_eq!(path.to_string(), "m/44'/60'/3'/0/1");
+
+        let path = derivation_path(ETH_PATH, None, Some(5)).unwrap();
+        assert_eq!(path.to_string(), "m/44'/60'/0'/0/5");
+    }
+
+    #[test]
+    fn rejects_unexpected_paths() {
+        assert!(derivation_path("m/44'/60'", Some(1), None).is_err());
+        assert!(derivation_path(ETH_PATH, Some(1 << 31), None).is_err());
+    }
+
+    #[test]
+    fn derives_from_12_and_24_word_mnemonics() {
+        let path = derivation_path(ETH_PATH, None, None).unwrap();
+        let twelve = "abandon abandon abandon abandon abandon abandon \
+                      abandon abandon abandon abandon abandon about";
+        let key = derive_key(twelve, &path).unwrap();
+        assert_eq!(
+            clarity::utils::bytes_to_hex_str(&key.to_bytes()),
+            "1ab42cc412b618bdea3a599e3c9bae199ebf030895b039e9db1e30dafb12b727"
+        );
+
+        let twenty_four = format!("{} art", ["abandon"; 23].join(" "));
+        assert!(derive_key(&twenty_four, &path).is_ok());
+        assert!(derive_key("abandon abandon", &path).is_err());
+    }
+}
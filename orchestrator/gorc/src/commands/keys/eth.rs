@@ -1,10 +1,17 @@
 mod add;
+mod addr_from_mnemonic;
 mod delete;
+mod export;
 mod import;
 mod list;
-mod show;
+mod rekey;
 mod rename;
+mod rotate;
+pub mod show;
+pub mod sign;
+pub mod verify;
 
+use super::menu::EthMenuCmd;
 use abscissa_core::{Command, Options, Runnable};
 
 #[derive(Command, Debug, Options, Runnable)]
@@ -15,15 +22,36 @@ pub enum EthKeysCmd {
     #[options(help = "import [name] (bip39-mnemonic)")]
     Import(import::ImportEthKeyCmd),
 
-    #[options(help = "delete [name]")]
+    #[options(help = "delete [name]...")]
     Delete(delete::DeleteEthKeyCmd),
 
+    #[options(help = "export [name]")]
+    Export(export::ExportEthKeyCmd),
+
     #[options(help = "rename [name] [new-name]")]
     Rename(rename::RenameEthKeyCmd),
 
-    #[options(help = "list")]
+    #[options(help = "rekey [name]")]
+    Rekey(rekey::RekeyEthKeyCmd),
+
+    #[options(help = "rotate [name]")]
+    Rotate(rotate::RotateEthKeyCmd),
+
+    #[options(help = "list (pattern)")]
     List(list::ListEthKeyCmd),
 
     #[options(help = "show [name]")]
     Show(show::ShowEthKeyCmd),
-}
\ No newline at end of file
+
+    #[options(help = "addr-from-mnemonic (bip39-mnemonic)")]
+    AddrFromMnemonic(addr_from_mnemonic::AddrFromMnemonicEthKeyCmd),
+
+    #[options(help = "sign [name] [message]")]
+    Sign(sign::SignEthKeyCmd),
+
+    #[options(help = "verify [address] [message] [signature]")]
+    Verify(verify::VerifyEthKeyCmd),
+
+    #[options(help = "choose a command interactively")]
+    Menu(EthMenuCmd),
+}
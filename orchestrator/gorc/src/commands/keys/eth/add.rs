@@ -1,11 +1,18 @@
-use super::show::ShowEthKeyCmd;
-use crate::application::APP;
+use super::show::{self, ShowEthKeyCmd};
+use crate::{
+    application::APP,
+    commands::keys::{
+        derivation,
+        keystore::{self, KeyMetadata, KeyName, KeyType},
+    },
+    error::KeysError,
+    prelude::*,
+};
 use abscissa_core::{Application, Command, Options, Runnable};
 use bip32;
 use k256::pkcs8::ToPrivateKey;
 use rand_core::OsRng;
-use signatory::FsKeyStore;
-use std::path;
+use std::{path::PathBuf, process};
 
 #[derive(Command, Debug, Default, Options)]
 pub struct AddEthKeyCmd {
@@ -14,45 +21,157 @@ pub struct AddEthKeyCmd {
 
     #[options(help = "overwrite existing key")]
     pub overwrite: bool,
+
+    #[options(help = "read the keystore password from a file")]
+    pub password_file: Option<PathBuf>,
+
+    #[options(no_short, help = "print the generated bip39-mnemonic (default: true)")]
+    pub show_mnemonic: Option<bool>,
+
+    #[options(help = "BIP44 account (default: from the configured derivation path)")]
+    pub account: Option<u32>,
+
+    #[options(help = "BIP44 address index (default: from the configured derivation path)")]
+    pub index: Option<u32>,
+
+    #[options(help = "generate this many keys named [name]-0 to [name]-(N-1)")]
+    pub num: Option<usize>,
 }
 
 // Entry point for `gorc keys eth add [name]`
-// - [name] required; key name
+// - [name] required; key name, or the base name with --num
 impl Runnable for AddEthKeyCmd {
     fn run(&self) {
         let config = APP.config();
-        let keystore = path::Path::new(&config.keystore);
-        let keystore = FsKeyStore::create_or_open(keystore).expect("Could not open keystore");
+        keystore::require_writable("keys eth add");
 
         let name = self.args.get(0).expect("name is required");
-        let name = name.parse().expect("Could not parse name");
-        if let Ok(_info) = keystore.info(&name) {
-            if !self.overwrite {
-                eprintln!("Key already exists, exiting.");
-                return;
+        if let Some(num) = self.num {
+            self.add_keys(name, num);
+            return;
+        }
+
+        let name = keystore::key_name(name);
+        if keystore::key_exists(&name) && !self.overwrite {
+            warn!("key '{}' already exists, exiting", name);
+            return;
+        }
+
+        let path = derivation::resolve(
+            &config.ethereum.key_derivation_path,
+            self.account,
+            self.index,
+        );
+
+        let password = keystore::store_password(&self.password_file);
+        self.add_key(&name, &path, password.as_deref())
+            .unwrap_or_else(|err| err.exit());
+
+        let args = vec![name.to_string()];
+        let show_cmd = ShowEthKeyCmd {
+            args,
+            password_file: self.password_file.clone(),
+            ..Default::default()
+        };
+        show_cmd.run();
+    }
+}
+
+impl AddEthKeyCmd {
+    /// Generate `num` keys named after `base`, printing the name and address
+    /// of each.
+    ///
+    /// All names are checked before the first key is written. If storing a
+    /// key fails, the keys created so far are reported before exiting.
+    fn add_keys(&self, base: &str, num: usize) {
+        let config = APP.config();
+
+        if num == 0 {
+            error!("--num must be at least 1");
+            process::exit(1);
+        }
+
+        let names: Vec<KeyName> = (0..num)
+            .map(|i| keystore::key_name(&format!("{}-{}", base, i)))
+            .collect();
+        let existing: Vec<&str> = names
+            .iter()
+            .filter(|name| keystore::key_exists(name))
+            .map(|name| name.as_ref())
+            .collect();
+        if !existing.is_empty() && !self.overwrite {
+            error!(
+                "keys already exist, pass --overwrite to replace them: {}",
+                existing.join(", ")
+            );
+            process::exit(1);
+        }
+
+        let path = derivation::resolve(
+            &config.ethereum.key_derivation_path,
+            self.account,
+            self.index,
+        );
+
+        // Ask for the password once rather than for every key.
+        let password = keystore::store_password(&self.password_file);
+        for (i, name) in names.iter().enumerate() {
+            match self.add_key(name, &path, password.as_deref()) {
+                Ok(address) => println!("{}\t{}", name, address),
+                Err(err) => {
+                    let created: Vec<&str> = names[..i].iter().map(|name| name.as_ref()).collect();
+                    error!("could not store key '{}': {}", name, err);
+                    error!(
+                        "created {} of {} keys: {}",
+                        created.len(),
+                        num,
+                        if created.is_empty() {
+                            "none".to_string()
+                        } else {
+                            created.join(", ")
+                        }
+                    );
+                    process::exit(1);
+                }
             }
         }
+    }
 
+    /// Generate a key from a new mnemonic and store it under the given name,
+    /// returning its address
+    pub fn add_key(
+        &self,
+        name: &KeyName,
+        path: &bip32::DerivationPath,
+        password: Option<&str>,
+    ) -> Result<String, KeysError> {
+        // The key is derived from the mnemonic the same way `import` does it,
+        // so importing this phrase later (with the same --account and
+        // --index) restores the identical address.
         let mnemonic = bip32::Mnemonic::random(&mut OsRng, Default::default());
-        eprintln!("**Important** record this bip39-mnemonic in a safe place:");
-        println!("{}", mnemonic.phrase());
+        if self.show_mnemonic.unwrap_or(true) {
+            eprintln!(
+                "**Important** write down this bip39-mnemonic for key '{}' and keep it in a safe place.",
+                name
+            );
+            eprintln!("It is the only way to recover this key and will not be shown again:");
+            eprintln!("{}", mnemonic.phrase());
+        }
 
         let seed = mnemonic.to_seed("");
 
-        let path = config.ethereum.key_derivation_path.trim();
-        let path = path
-            .parse::<bip32::DerivationPath>()
-            .expect("Could not parse derivation path");
-
-        let key = bip32::XPrv::derive_from_path(seed, &path).expect("Could not derive key");
+        let key = bip32::XPrv::derive_from_path(seed, path).expect("Could not derive key");
         let key = k256::SecretKey::from(key.private_key());
+        let address = show::address(&key);
         let key = key
             .to_pkcs8_der()
             .expect("Could not PKCS8 encod private key");
-        keystore.store(&name, &key).expect("Could not store key");
+        keystore::try_store_key(name, &key, password)?;
+        keystore::write_metadata(
+            name,
+            &KeyMetadata::new(KeyType::Eth, Some(&path.to_string())),
+        )?;
 
-        let args = vec![name.to_string()];
-        let show_cmd = ShowEthKeyCmd { args };
-        show_cmd.run();
+        Ok(address)
     }
 }
@@ -1,24 +1,95 @@
-use crate::application::APP;
-use abscissa_core::{Application, Command, Options, Runnable};
-use signatory::FsKeyStore;
-use std::path;
+use super::show::ShowEthKeyCmd;
+use crate::{
+    commands::keys::{confirm, keystore},
+    prelude::*,
+};
+use abscissa_core::{Command, Options, Runnable};
+use std::{path::PathBuf, process};
 
 #[derive(Command, Debug, Default, Options)]
 pub struct DeleteEthKeyCmd {
-    #[options(free, help = "delete [name]")]
+    #[options(free, help = "delete [name]...")]
     pub args: Vec<String>,
+
+    #[options(help = "read the keystore password from a file")]
+    pub password_file: Option<PathBuf>,
+
+    #[options(help = "print the key files that would be deleted without deleting it")]
+    pub dry_run: bool,
+
+    #[options(help = "skip the confirmation prompt")]
+    pub yes: bool,
 }
 
-// Entry point for `gorc keys eth delete [name]`
-// - [name] required; key name
+// Entry point for `gorc keys eth delete [name]...`
+// - [name] required; one or more key names
+//
+// Each key is deleted on its own: a key that doesn't exist or isn't
+// confirmed is reported and the rest are still deleted, with a non-zero exit
+// if any of them failed. The password for encrypted keys is asked for once.
 impl Runnable for DeleteEthKeyCmd {
     fn run(&self) {
-        let config = APP.config();
-        let keystore = path::Path::new(&config.keystore);
-        let keystore = FsKeyStore::create_or_open(keystore).expect("Could not open keystore");
+        if self.args.is_empty() {
+            error!("at least one key name is required");
+            process::exit(1);
+        }
+        if !self.dry_run {
+            keystore::require_writable("keys eth delete");
+        }
+
+        let mut password = keystore::SharedPassword::new(&self.password_file);
+        let mut failed = Vec::new();
+        for name in &self.args {
+            if let Err(err) = self.delete(name, &mut password) {
+                error!("could not delete key '{}': {}", name, err);
+                failed.push(name.as_str());
+            }
+        }
+        if !failed.is_empty() {
+            error!(
+                "{} of {} keys not deleted: {}",
+                failed.len(),
+                self.args.len(),
+                failed.join(", ")
+            );
+            process::exit(1);
+        }
+    }
+}
+
+impl DeleteEthKeyCmd {
+    fn delete(
+        &self,
+        name: &str,
+        password: &mut keystore::SharedPassword<'_>,
+    ) -> Result<(), String> {
+        let name = keystore::validate_key_name(name).map_err(|err| err.to_string())?;
+        if !keystore::key_exists(&name) {
+            return Err("key does not exist".to_string());
+        }
+
+        // A key that can't be loaded can still be deleted by its path.
+        let info = password
+            .load_key(&name)
+            .and_then(|key| ShowEthKeyCmd::info(&name, &key));
+        let address = match info {
+            Ok(info) => info.address,
+            Err(err) => format!("address unavailable: {}", err),
+        };
+
+        let location = keystore::key_location(&name);
+        if self.dry_run {
+            println!("would delete {}\t{}", location, address);
+            return Ok(());
+        }
+
+        let prompt = format!("Delete key '{}' ({}) at {}?", name, address, location);
+        if !self.yes && !confirm(&prompt) {
+            return Err("not confirmed (pass --yes)".to_string());
+        }
 
-        let name = self.args.get(0).expect("name is required");
-        let name = name.parse().expect("Could not parse name");
-        keystore.delete(&name).expect("Could not delete key");
+        keystore::delete_key(&name).map_err(|err| err.to_string())?;
+        info!("deleted key '{}'", name);
+        Ok(())
     }
 }
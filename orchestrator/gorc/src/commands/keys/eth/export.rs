@@ -0,0 +1,92 @@
+use crate::{
+    application::APP,
+    commands::keys::{
+        confirm,
+        keystore::{self, KeyName},
+    },
+    config::KeystoreFormat,
+    prelude::*,
+};
+use abscissa_core::{Application, Command, Options, Runnable};
+use std::{path::PathBuf, process};
+
+#[derive(Command, Debug, Default, Options)]
+pub struct ExportEthKeyCmd {
+    #[options(free, help = "export [name]")]
+    pub args: Vec<String>,
+
+    #[options(no_short, help = "write the exported key to a file instead of stdout")]
+    pub out_file: Option<PathBuf>,
+
+    #[options(no_short, help = "export the raw private key as unencrypted hex")]
+    pub unsafe_unarmored: bool,
+
+    #[options(help = "skip the confirmation prompt for unencrypted exports")]
+    pub yes: bool,
+
+    #[options(help = "read the keystore password from a file")]
+    pub password_file: Option<PathBuf>,
+}
+
+// Entry point for `gorc keys eth export [name]`
+// - [name] required; key name
+//
+// Keys are exported in the configured keystore format (`--keystore-format`),
+// encrypted with a password.
+impl Runnable for ExportEthKeyCmd {
+    fn run(&self) {
+        let config = APP.config();
+
+        let name = self.args.get(0).expect("name is required");
+        let name = keystore::key_name(name);
+
+        if !keystore::key_exists(&name) {
+            error!("key '{}' does not exist", name);
+            process::exit(1);
+        }
+
+        let json = config.keystore_format == KeystoreFormat::Json;
+        let exported = if self.unsafe_unarmored {
+            if !self.yes && !confirm("Export the unencrypted private key?") {
+                error!("refusing to export unencrypted key without confirmation (pass --yes)");
+                process::exit(1);
+            }
+
+            let key = keystore::load_key(&name, &self.password_file);
+            let key = keystore::secret_key(&key).expect("Could not parse key");
+            format!("0x{}\n", clarity::utils::bytes_to_hex_str(&key.to_bytes()))
+        } else if let Some(stored) = stored_encrypted(&name, json) {
+            // Already stored encrypted in the requested format.
+            stored
+        } else {
+            // Other keys are (re-)armored with a password for export.
+            let key = keystore::load_key(&name, &self.password_file);
+            let password = keystore::export_password(&self.password_file);
+
+            if json {
+                format!("{}\n", keystore::encrypt_json_key(&key, &password))
+            } else {
+                keystore::encrypt_key(&key, &password).to_pem().to_string()
+            }
+        };
+
+        match &self.out_file {
+            Some(path) => {
+                if let Err(err) = keystore::write_secret_file(path, exported.as_bytes()) {
+                    error!("could not write {}: {}", path.display(), err);
+                    process::exit(1);
+                }
+                info!("exported key '{}' to {}", name, path.display());
+            }
+            None => print!("{}", exported),
+        }
+    }
+}
+
+/// The key as stored if it is encrypted and in the requested format; JSON
+/// keystores always are encrypted
+fn stored_encrypted(name: &KeyName, json: bool) -> Option<String> {
+    let stored = keystore::stored_key(name).expect("Could not load key");
+    let is_json = keystore::json::is_json(&stored);
+    Some(stored).filter(|stored| is_json == json && keystore::is_encrypted(stored))
+}
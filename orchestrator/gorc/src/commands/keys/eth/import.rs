@@ -1,63 +1,140 @@
 use super::show::ShowEthKeyCmd;
-use crate::application::APP;
-use abscissa_core::{Application, Command, Options, Runnable};
+use crate::{
+    application::APP,
+    commands::keys::{
+        derivation,
+        keystore::{self, json, KeyMetadata, KeyType},
+        read_secret,
+    },
+    prelude::*,
+};
+use abscissa_core::{Command, Options, Runnable};
 use k256::pkcs8::ToPrivateKey;
-use signatory::FsKeyStore;
-use std::path;
+use std::{path::PathBuf, process};
 
 #[derive(Command, Debug, Default, Options)]
 pub struct ImportEthKeyCmd {
-    #[options(free, help = "import [name] (bip39-mnemonic)")]
+    #[options(
+        free,
+        help = "import [name] (bip39-mnemonic|hex-private-key|json-keystore)"
+    )]
     pub args: Vec<String>,
 
     #[options(help = "overwrite existing key")]
     pub overwrite: bool,
+
+    #[options(help = "read the keystore password from a file")]
+    pub password_file: Option<PathBuf>,
+
+    #[options(help = "read the hex private key or JSON keystore from a file")]
+    pub key_file: Option<PathBuf>,
+
+    #[options(help = "BIP44 account (default: from the configured derivation path)")]
+    pub account: Option<u32>,
+
+    #[options(help = "BIP44 address index (default: from the configured derivation path)")]
+    pub index: Option<u32>,
 }
 
-// Entry point for `gorc keys eth import [name] (bip39-mnemonic)`
+// Entry point for `gorc keys eth import [name] (bip39-mnemonic|hex-private-key|json-keystore)`
 // - [name] required; key name
-// - (bip39-mnemonic) optional; when absent the user will be prompted to enter it
+// - (bip39-mnemonic|hex-private-key|json-keystore) optional; when absent or
+//   `-` it is read from --key-file or stdin, or the user will be prompted to
+//   enter it.
+//   JSON keystores (e.g. from geth) are decrypted with the keystore password.
 impl Runnable for ImportEthKeyCmd {
     fn run(&self) {
         let config = APP.config();
-        let keystore = path::Path::new(&config.keystore);
-        let keystore = FsKeyStore::create_or_open(keystore).expect("Could not open keystore");
+        keystore::require_writable("keys eth import");
 
         let name = self.args.get(0).expect("name is required");
-        let name = name.parse().expect("Could not parse name");
-        if let Ok(_info) = keystore.info(&name) {
-            if !self.overwrite {
-                eprintln!("Key already exists, exiting.");
-                return;
-            }
+        let name = keystore::key_name(name);
+        if keystore::key_exists(&name) && !self.overwrite {
+            warn!("key '{}' already exists, exiting", name);
+            return;
         }
 
-        let mnemonic = match self.args.get(1) {
-            Some(mnemonic) => mnemonic.clone(),
-            None => rpassword::read_password_from_tty(Some("> Enter your bip39-mnemonic:\n"))
-                .expect("Could not read mnemonic"),
-        };
+        let secret = read_secret(
+            self.args.get(1),
+            &self.key_file,
+            "bip39-mnemonic or hex private key",
+        );
+        let secret = secret.trim();
 
-        let mnemonic = bip32::Mnemonic::new(mnemonic.trim(), Default::default())
-            .expect("Could not parse mnemonic");
+        let (key, derivation_path) = if json::is_json(secret) {
+            let password = keystore::unlock_password(&name, &self.password_file)
+                .unwrap_or_else(|err| err.exit());
+            let key = json::decrypt(secret, &password).unwrap_or_else(|err| {
+                error!("{}", err);
+                process::exit(1);
+            });
+            (key, None)
+        } else if is_hex_key(secret) {
+            if self.account.is_some() || self.index.is_some() {
+                warn!("--account and --index are ignored for raw private keys");
+            }
 
-        let seed = mnemonic.to_seed("");
+            let key = parse_hex_key(secret).unwrap_or_else(|err| {
+                error!("{}", err);
+                process::exit(1);
+            });
+            (key, None)
+        } else {
+            let path = derivation::resolve(
+                &config.ethereum.key_derivation_path,
+                self.account,
+                self.index,
+            );
 
-        let path = config.ethereum.key_derivation_path.trim();
-        let path = path
-            .parse::<bip32::DerivationPath>()
-            .expect("Could not parse derivation path");
+            let key = derivation::derive_key(secret, &path).unwrap_or_else(|err| {
+                error!("{}", err);
+                process::exit(1);
+            });
+            (key, Some(path.to_string()))
+        };
 
-        let key = bip32::XPrv::derive_from_path(seed, &path).expect("Could not derive key");
-        let key = k256::SecretKey::from(key.private_key());
         let key = key
             .to_pkcs8_der()
             .expect("Could not PKCS8 encod private key");
 
-        keystore.store(&name, &key).expect("Could not store key");
+        keystore::store_key(&name, &key, &self.password_file);
+        keystore::write_metadata(
+            &name,
+            &KeyMetadata::new(KeyType::Eth, derivation_path.as_deref()),
+        )
+        .unwrap_or_else(|err| err.exit());
 
         let args = vec![name.to_string()];
-        let show_cmd = ShowEthKeyCmd { args };
+        let show_cmd = ShowEthKeyCmd {
+            args,
+            password_file: self.password_file.clone(),
+            ..Default::default()
+        };
         show_cmd.run();
     }
 }
+
+/// Mnemonics always contain whitespace, so a secret made up only of hex
+/// digits is treated as a raw private key.
+fn is_hex_key(secret: &str) -> bool {
+    let hex = secret.strip_prefix("0x").unwrap_or(secret);
+    !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Parse an optionally 0x-prefixed, hex encoded secp256k1 private key
+fn parse_hex_key(secret: &str) -> Result<k256::SecretKey, String> {
+    let hex = secret.strip_prefix("0x").unwrap_or(secret);
+    if hex.len() != 64 {
+        return Err(format!(
+            "private key must be exactly 32 bytes, got {} hex characters",
+            hex.len()
+        ));
+    }
+
+    let bytes = clarity::utils::hex_str_to_bytes(hex)
+        .map_err(|_| "private key is not valid hex".to_string())?;
+
+    k256::NonZeroScalar::from_repr(*k256::FieldBytes::from_slice(&bytes))
+        .map(k256::SecretKey::from)
+        .ok_or_else(|| "private key is out of range for secp256k1".to_string())
+}
@@ -1,30 +1,145 @@
 use super::show::ShowEthKeyCmd;
-use crate::application::APP;
-use abscissa_core::{Application, Command, Options, Runnable};
-use std::path;
+use crate::{
+    commands::keys::{
+        keystore::{self, KeyType},
+        list::{or_dash, KeyList, ListedKey, SortBy, LOCKED},
+    },
+    error::KeysError,
+    output::{self, OutputFormat},
+    prelude::*,
+};
+use abscissa_core::{Command, Options, Runnable};
+use std::{path::PathBuf, process};
 
 #[derive(Command, Debug, Default, Options)]
-pub struct ListEthKeyCmd {}
+pub struct ListEthKeyCmd {
+    #[options(free, help = "list (pattern)")]
+    pub args: Vec<String>,
 
-// Entry point for `gorc keys eth list`
+    #[options(help = "only list keys whose name starts with this prefix")]
+    pub prefix: Option<String>,
+
+    #[options(no_short, help = "only print the number of keys")]
+    pub count: bool,
+
+    #[options(no_short, help = "order of the keys (name|mtime)")]
+    pub sort_by: SortBy,
+
+    #[options(no_short, help = "also print each key's public key fingerprint")]
+    pub fingerprint: bool,
+
+    #[options(short = "o", help = "output format (text|json)")]
+    pub output: OutputFormat,
+
+    #[options(help = "also list keys namespaced in subdirectories of the keystore")]
+    pub recursive: bool,
+
+    #[options(help = "read the keystore password from a file")]
+    pub password_file: Option<PathBuf>,
+
+    #[options(no_short, help = "wrap -o json output in a versioned object")]
+    pub json_schema: bool,
+}
+
+// Entry point for `gorc keys eth list (pattern)`
+// - (pattern) optional; glob the key names must match, e.g. 'validator*'
 impl Runnable for ListEthKeyCmd {
     fn run(&self) {
-        let config = APP.config();
-        let keystore = path::Path::new(&config.keystore);
-
-        for entry in keystore.read_dir().expect("Could not read keystore") {
-            let path = entry.unwrap().path();
-            if path.is_file() {
-                if let Some(extension) = path.extension() {
-                    if extension == "pem" {
-                        let name = path.file_stem().unwrap();
-                        let name = name.to_str().unwrap();
-                        let args = vec![name.to_string()];
-                        let show_cmd = ShowEthKeyCmd { args };
-                        show_cmd.run();
+        if self.json_schema && self.output != OutputFormat::Json {
+            error!("--json-schema requires -o json");
+            process::exit(1);
+        }
+        self.try_run().unwrap_or_else(|err| err.exit());
+    }
+}
+
+impl ListEthKeyCmd {
+    fn try_run(&self) -> Result<(), KeysError> {
+        let pattern = self.args.get(0).map(String::as_str);
+
+        let mut password = keystore::SharedPassword::new(&self.password_file);
+        let mut keys = Vec::new();
+        let mut failed = 0;
+        for name in keystore::names(self.recursive)? {
+            let name = match name {
+                Ok(name) => name,
+                Err(err) => {
+                    warn!("skipping: {}", err);
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            if !keystore::name_matches(&name, pattern, self.prefix.as_deref()) {
+                continue;
+            }
+            match keystore::has_type(&name, KeyType::Eth) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(err) => {
+                    warn!("skipping: {}", err);
+                    failed += 1;
+                    continue;
+                }
+            }
+
+            // A broken or locked key file shouldn't hide the healthy keys.
+            let info = password
+                .load_key(&name)
+                .and_then(|key| ShowEthKeyCmd::info(&name, &key))
+                .map(|info| (info, false))
+                .or_else(|err| match err {
+                    KeysError::NoPassword { .. } => {
+                        ShowEthKeyCmd::locked_info(&name).map(|info| (info, true))
                     }
+                    err => Err(err),
+                });
+            match info {
+                Ok((info, locked)) => keys.push(ListedKey {
+                    info,
+                    modified: keystore::key_modified(&name),
+                    locked,
+                }),
+                Err(err) => {
+                    warn!("skipping: {}", err);
+                    failed += 1;
+                }
+            }
+        }
+
+        self.sort_by.sort(&mut keys, |key| key.modified.as_deref());
+
+        if self.count {
+            println!("{}", keys.len());
+        } else {
+            match self.output {
+                OutputFormat::Text => {
+                    let rows: Vec<Vec<&str>> = keys
+                        .iter()
+                        .map(|key| {
+                            let mut row = vec![key.info.name.as_str(), or_dash(&key.info.address)];
+                            if self.fingerprint {
+                                row.push(or_dash(&key.info.fingerprint));
+                            }
+                            row.push(key.modified.as_deref().unwrap_or("-"));
+                            if key.locked {
+                                row.push(LOCKED);
+                            }
+                            row
+                        })
+                        .collect();
+                    output::print_table(&rows);
                 }
+                OutputFormat::Json if self.json_schema => {
+                    output::print_versioned_json(&KeyList { keys })
+                }
+                OutputFormat::Json => output::print_json(&keys),
             }
         }
+
+        if failed > 0 {
+            return Err(KeysError::Skipped(failed));
+        }
+        Ok(())
     }
 }
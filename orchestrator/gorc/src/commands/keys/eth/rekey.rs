@@ -0,0 +1,75 @@
+use crate::{
+    application::APP,
+    commands::keys::keystore::{self, json},
+    error::KeysError,
+    prelude::*,
+};
+use abscissa_core::{Application, Command, Options, Runnable};
+use std::{path::PathBuf, process};
+
+#[derive(Command, Debug, Default, Options)]
+pub struct RekeyEthKeyCmd {
+    #[options(free, help = "rekey [name]")]
+    pub args: Vec<String>,
+
+    #[options(help = "read the keystore password from a file")]
+    pub password_file: Option<PathBuf>,
+}
+
+// Entry point for `gorc keys eth rekey [name]`
+// - [name] required; key name
+//
+// Re-encrypts the key with the same password using the KDF parameters from
+// the `[keystore_encryption]` config section.
+impl Runnable for RekeyEthKeyCmd {
+    fn run(&self) {
+        let config = APP.config();
+        keystore::require_writable("keys eth rekey");
+        let name = self.args.get(0).expect("name is required");
+        let name = keystore::key_name(name);
+
+        if !keystore::key_exists(&name) {
+            error!("key '{}' does not exist", name);
+            process::exit(1);
+        }
+
+        let stored = keystore::stored_key(&name).unwrap_or_else(|source| {
+            KeysError::LoadKey {
+                name: name.to_string(),
+                source,
+            }
+            .exit()
+        });
+        let key = if json::is_json(&stored) {
+            // JSON keystores are always encrypted and stay JSON.
+            let password = keystore::unlock_password(&name, &self.password_file)
+                .unwrap_or_else(|err| err.exit());
+            let key = json::decrypt(&stored, &password).unwrap_or_else(|err| {
+                error!("{}", err);
+                process::exit(1);
+            });
+            json::encrypt(&key, &password, keystore::configured_scrypt_params())
+        } else {
+            if !keystore::is_encrypted(&stored) {
+                error!("key '{}' is not encrypted", name);
+                process::exit(1);
+            }
+
+            let password = keystore::unlock_password(&name, &self.password_file)
+                .unwrap_or_else(|err| err.exit());
+            let key = keystore::try_load_key_with(&name, || Ok(password.clone()))
+                .unwrap_or_else(|err| err.exit());
+            keystore::encrypt_key(&key, &password).to_pem().to_string()
+        };
+        if let Err(err) = keystore::backend().store(&name, &key) {
+            error!("could not store key '{}': {}", name, err);
+            process::exit(1);
+        }
+
+        let params = &config.keystore_encryption;
+        info!(
+            "re-encrypted key '{}' with scrypt n={} r={} p={}",
+            name, params.n, params.r, params.p
+        );
+    }
+}
@@ -1,7 +1,6 @@
-use crate::application::APP;
-use abscissa_core::{Application, Command, Options, Runnable};
-use signatory::FsKeyStore;
-use std::path;
+use crate::{commands::keys::keystore, error::KeysError, prelude::*};
+use abscissa_core::{Command, Options, Runnable};
+use std::process;
 
 #[derive(Command, Debug, Default, Options)]
 pub struct RenameEthKeyCmd {
@@ -15,26 +14,56 @@ pub struct RenameEthKeyCmd {
 // Entry point for `gorc keys eth rename [name] [new-name]`
 impl Runnable for RenameEthKeyCmd {
     fn run(&self) {
-        let config = APP.config();
-        let keystore = path::Path::new(&config.keystore);
-        let keystore = FsKeyStore::create_or_open(keystore).expect("Could not open keystore");
-
+        keystore::require_writable("keys eth rename");
         let name = self.args.get(0).expect("name is required");
-        let name = name.parse().expect("Could not parse name");
+        let name = keystore::key_name(name);
 
         let new_name = self.args.get(1).expect("new-name is required");
-        let new_name = new_name.parse().expect("Could not parse new-name");
-        if let Ok(_info) = keystore.info(&new_name) {
-            if !self.overwrite {
-                eprintln!("Key already exists, exiting.");
-                return;
-            }
+        let new_name = keystore::key_name(new_name);
+
+        // Storing the key under its own name and then deleting the source
+        // would leave no copy of it.
+        if new_name == name {
+            error!("key '{}' can't be renamed to itself", name);
+            process::exit(1);
+        }
+
+        if !keystore::key_exists(&name) {
+            error!("key '{}' does not exist", name);
+            process::exit(1);
         }
 
-        let key = keystore.load(&name).expect("Could not load key");
-        keystore
-            .store(&new_name, &key)
-            .expect("Could not store key");
-        keystore.delete(&name).expect("Could not delete key");
+        if keystore::key_exists(&new_name) && !self.overwrite {
+            error!("key '{}' already exists", new_name);
+            process::exit(1);
+        }
+
+        // Move the stored contents as they are, so encrypted keys are renamed
+        // without a password and keep their format. Storing replaces an
+        // overwritten key in either format.
+        let backend = keystore::backend();
+        let key = backend.load(&name).unwrap_or_else(|source| {
+            KeysError::LoadKey {
+                name: name.to_string(),
+                source,
+            }
+            .exit()
+        });
+        if let Err(err) = backend.store(&new_name, &key) {
+            error!(
+                "could not store key '{}', '{}' is unchanged: {}",
+                new_name, name, err
+            );
+            process::exit(1);
+        }
+        if let Err(err) = backend.delete(&name) {
+            error!(
+                "copied key '{}' to '{}' but could not delete it: {}",
+                name, new_name, err
+            );
+            process::exit(1);
+        }
+        keystore::rename_metadata(&name, &new_name).unwrap_or_else(|err| err.exit());
+        info!("renamed key '{}' to '{}'", name, new_name);
     }
 }
@@ -0,0 +1,271 @@
+use super::{add::AddEthKeyCmd, show::ShowEthKeyCmd};
+use crate::{
+    application::APP,
+    commands::keys::{derivation, keystore},
+    error::KeysError,
+    output::{self, OutputFormat},
+    prelude::*,
+};
+use abscissa_core::{Application, Command, Options, Runnable};
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+    time::SystemTime,
+};
+
+#[derive(Command, Debug, Default, Options)]
+pub struct RotateEthKeyCmd {
+    #[options(free, help = "rotate [name]")]
+    pub args: Vec<String>,
+
+    #[options(help = "read the keystore password from a file")]
+    pub password_file: Option<PathBuf>,
+
+    #[options(no_short, help = "print the generated bip39-mnemonic (default: true)")]
+    pub show_mnemonic: Option<bool>,
+
+    #[options(help = "BIP44 account (default: from the configured derivation path)")]
+    pub account: Option<u32>,
+
+    #[options(help = "BIP44 address index (default: from the configured derivation path)")]
+    pub index: Option<u32>,
+
+    #[options(help = "delete all but the newest N backups of the key")]
+    pub keep_backups: Option<usize>,
+
+    #[options(short = "o", help = "output format (text|json)")]
+    pub output: OutputFormat,
+}
+
+/// Outcome of rotating a key
+#[derive(Debug, Serialize)]
+pub struct RotatedKey {
+    pub name: String,
+    pub old_address: String,
+    pub new_address: String,
+    /// File the old key was moved to
+    pub backup: String,
+    /// Older backups deleted because of `--keep-backups`
+    pub pruned: Vec<String>,
+}
+
+// Entry point for `gorc keys eth rotate [name]`
+// - [name] required; key name
+//
+// The old key file is moved to `<file>.bak-<timestamp>` next to it, where
+// `list` and `audit` don't pick it up, and a key from a new mnemonic is
+// stored under the same name.
+impl Runnable for RotateEthKeyCmd {
+    fn run(&self) {
+        let config = APP.config();
+        keystore::require_file_backend("keys eth rotate");
+        keystore::require_writable("keys eth rotate");
+
+        let name = self.args.get(0).expect("name is required");
+        let name = keystore::key_name(name);
+        if !keystore::key_exists(&name) {
+            error!("key '{}' does not exist", name);
+            process::exit(1);
+        }
+
+        // Loading the old key proves the password is known before anything
+        // is moved.
+        let old_address = ShowEthKeyCmd::try_key_info(&name, &self.password_file)
+            .unwrap_or_else(|err| {
+                error!("{}", err);
+                process::exit(1);
+            })
+            .address;
+
+        let path = keystore::stored_key_path(&name);
+        let backup = backup_path(&path, SystemTime::now());
+        if backup.exists() {
+            error!("backup {} already exists", backup.display());
+            process::exit(1);
+        }
+
+        let derivation_path = derivation::resolve(
+            &config.ethereum.key_derivation_path,
+            self.account,
+            self.index,
+        );
+        let add_cmd = AddEthKeyCmd {
+            password_file: self.password_file.clone(),
+            show_mnemonic: self.show_mnemonic,
+            ..Default::default()
+        };
+        let password = keystore::store_password(&self.password_file);
+
+        if let Err(err) = fs::rename(&path, &backup) {
+            error!(
+                "could not back up key '{}' to {}: {}",
+                name,
+                backup.display(),
+                err
+            );
+            process::exit(1);
+        }
+        let new_address = add_cmd
+            .add_key(&name, &derivation_path, password.as_deref())
+            .unwrap_or_else(|err| {
+                error!("could not store new key '{}': {}", name, err);
+                if let Err(err) = fs::rename(&backup, &path) {
+                    error!(
+                        "could not restore key '{}' from {}: {}",
+                        name,
+                        backup.display(),
+                        err
+                    );
+                }
+                process::exit(1);
+            });
+
+        // The key is rotated by now, so a failure to prune is only reported.
+        let pruned = match self.keep_backups {
+            Some(keep) => prune_backups(&path, keep).unwrap_or_else(|err| {
+                warn!("could not prune old backups: {}", err);
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+
+        let rotated = RotatedKey {
+            name: name.to_string(),
+            old_address,
+            new_address,
+            backup: backup.display().to_string(),
+            pruned: pruned
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect(),
+        };
+        match self.output {
+            OutputFormat::Text => {
+                output::print_fields(&[
+                    ("name", &rotated.name),
+                    ("old_address", &rotated.old_address),
+                    ("new_address", &rotated.new_address),
+                    ("backup", &rotated.backup),
+                ]);
+                for path in &rotated.pruned {
+                    info!("deleted old backup {}", path);
+                }
+            }
+            OutputFormat::Json => output::print_json(&rotated),
+        }
+    }
+}
+
+/// `<file>.bak-<timestamp>` with a UTC timestamp like `20211013T142520Z`,
+/// which sorts chronologically
+fn backup_path(path: &Path, time: SystemTime) -> PathBuf {
+    let timestamp: String = humantime::format_rfc3339_seconds(time)
+        .to_string()
+        .chars()
+        .filter(|c| *c != '-' && *c != ':')
+        .collect();
+
+    let mut file_name = path
+        .file_name()
+        .expect("key path has a file name")
+        .to_owned();
+    file_name.push(format!(".bak-{}", timestamp));
+    path.with_file_name(file_name)
+}
+
+/// Delete all but the newest `keep` backups of the key stored at `path`,
+/// returning the deleted files. Backups that can't be deleted are reported
+/// and kept.
+fn prune_backups(path: &Path, keep: usize) -> Result<Vec<PathBuf>, KeysError> {
+    let dir = path.parent().expect("key path has a parent");
+    let stem = path.file_stem().expect("key path has a file stem");
+
+    let mut backups = Vec::new();
+    let entries = fs::read_dir(dir).map_err(|source| KeysError::ReadKeystore {
+        path: dir.to_path_buf(),
+        source,
+    })?;
+    for entry in entries {
+        let backup = entry
+            .map_err(|source| KeysError::ReadEntry {
+                path: dir.to_path_buf(),
+                source,
+            })?
+            .path();
+        if let Some(timestamp) = backup_timestamp(&backup, stem.to_str()) {
+            backups.push((timestamp, backup));
+        }
+    }
+
+    let mut pruned = select_pruned(backups, keep);
+    pruned.retain(|backup| match fs::remove_file(backup) {
+        Ok(()) => true,
+        Err(err) => {
+            warn!("could not delete backup {}: {}", backup.display(), err);
+            false
+        }
+    });
+    Ok(pruned)
+}
+
+/// Timestamp of a backup of the named key, in either key format
+fn backup_timestamp(path: &Path, name: Option<&str>) -> Option<String> {
+    let file_name = path.file_name()?.to_str()?;
+    let rest = file_name.strip_prefix(name?)?;
+    let timestamp = rest
+        .strip_prefix(".pem.bak-")
+        .or_else(|| rest.strip_prefix(".json.bak-"))?;
+    Some(timestamp.to_string())
+}
+
+/// The backups beyond the newest `keep`, oldest first
+fn select_pruned(mut backups: Vec<(String, PathBuf)>, keep: usize) -> Vec<PathBuf> {
+    backups.sort();
+    let excess = backups.len().saturating_sub(keep);
+    backups
+        .into_iter()
+        .take(excess)
+        .map(|(_, path)| path)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{backup_path, backup_timestamp, select_pruned};
+    use std::{
+        path::{Path, PathBuf},
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    #[test]
+    fn prunes_oldest_backups() {
+        let time = UNIX_EPOCH + Duration::from_secs(1_634_135_120);
+        let backup = backup_path(Path::new("/keys/orch.pem"), time);
+        assert_eq!(backup, Path::new("/keys/orch.pem.bak-20211013T142520Z"));
+        assert_eq!(
+            backup_timestamp(&backup, Some("orch")).as_deref(),
+            Some("20211013T142520Z")
+        );
+        assert_eq!(backup_timestamp(&backup, Some("orc")), None);
+        assert_eq!(
+            backup_timestamp(Path::new("/keys/orch.pem"), Some("orch")),
+            None
+        );
+
+        let backups: Vec<(String, PathBuf)> =
+            ["20211013T142520Z", "20200101T000000Z", "20211101T000000Z"]
+                .iter()
+                .map(|timestamp| (timestamp.to_string(), PathBuf::from(timestamp)))
+                .collect();
+        assert_eq!(
+            select_pruned(backups.clone(), 1),
+            vec![
+                PathBuf::from("20200101T000000Z"),
+                PathBuf::from("20211013T142520Z")
+            ]
+        );
+        assert!(select_pruned(backups, 3).is_empty());
+    }
+}
@@ -1,35 +1,270 @@
-use crate::application::APP;
+use crate::{
+    application::APP,
+    commands::keys::keystore::{self, KeyName},
+    config::Source,
+    error::KeysError,
+    output::{self, OutputFormat},
+    prelude::*,
+    qr,
+};
 use abscissa_core::{Application, Command, Options, Runnable};
 use clarity;
-use signatory::FsKeyStore;
-use std::path;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use pkcs8::PrivateKeyDocument;
+use serde::Serialize;
+use std::{path::PathBuf, process, time::Duration};
+
+/// How long to wait for the RPC endpoint when querying a balance
+const BALANCE_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Command, Debug, Default, Options)]
 pub struct ShowEthKeyCmd {
     #[options(free, help = "show [name]")]
     pub args: Vec<String>,
+
+    #[options(short = "o", help = "output format (text|json)")]
+    pub output: OutputFormat,
+
+    #[options(help = "read the keystore password from a file")]
+    pub password_file: Option<PathBuf>,
+
+    #[options(help = "also print the address as a QR code")]
+    pub qr: bool,
+
+    #[options(help = "print the address in lowercase instead of EIP-55 mixed case")]
+    pub lowercase: bool,
+
+    #[options(no_short, help = "also query the address's ETH balance over RPC")]
+    pub balance: bool,
+
+    #[options(
+        no_short,
+        help = "Ethereum RPC endpoint for --balance (default: ethereum.rpc)"
+    )]
+    pub rpc_url: Option<String>,
+}
+
+/// Details of a stored Ethereum key
+#[derive(Debug, Serialize)]
+pub struct EthKeyInfo {
+    pub name: String,
+    /// Empty for locked keys without a recorded address
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub address: String,
+    /// Compressed SEC1 public key, 0x-prefixed hex, empty for locked keys
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub public_key: String,
+    /// Fingerprint of the public key, see [`keystore::fingerprint`], empty
+    /// for locked keys
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub fingerprint: String,
+    /// BIP44 path the key was derived along, `None` if it wasn't HD-derived
+    pub derivation_path: Option<String>,
+}
+
+/// ETH balance of an address, as decimal strings
+#[derive(Debug, Serialize)]
+pub struct Balance {
+    pub wei: String,
+    pub ether: String,
+}
+
+/// Key details printed by `show`, with the balance if it was queried
+#[derive(Debug, Serialize)]
+struct ShownEthKey {
+    #[serde(flatten)]
+    info: EthKeyInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    balance: Option<Balance>,
+}
+
+impl ShowEthKeyCmd {
+    /// Load the named key from the keystore and derive its address
+    pub fn try_key_info(
+        name: &str,
+        password_file: &Option<PathBuf>,
+    ) -> Result<EthKeyInfo, KeysError> {
+        let name = keystore::validate_key_name(name)?;
+
+        let key = keystore::try_load_key(&name, password_file)?;
+        Self::info(&name, &key)
+    }
+
+    /// Details of the named key, already loaded from the keystore
+    pub fn info(name: &KeyName, key: &PrivateKeyDocument) -> Result<EthKeyInfo, KeysError> {
+        let key = keystore::secret_key(key).map_err(|source| KeysError::LoadKey {
+            name: name.to_string(),
+            source,
+        })?;
+        let public_key = key.public_key().to_encoded_point(true);
+
+        Ok(EthKeyInfo {
+            name: name.to_string(),
+            address: address(&key),
+            public_key: format!(
+                "0x{}",
+                clarity::utils::bytes_to_hex_str(public_key.as_bytes())
+            ),
+            fingerprint: keystore::fingerprint(&key),
+            derivation_path: keystore::read_metadata(name)?.derivation_path,
+        })
+    }
+
+    /// What is known of an encrypted key without its password: the address
+    /// JSON keystores record and the key's metadata
+    pub fn locked_info(name: &KeyName) -> Result<EthKeyInfo, KeysError> {
+        Ok(EthKeyInfo {
+            name: name.to_string(),
+            address: keystore::stored_address(name).unwrap_or_default(),
+            public_key: String::new(),
+            fingerprint: String::new(),
+            derivation_path: keystore::read_metadata(name)?.derivation_path,
+        })
+    }
+
+    /// Query the address's balance from `--rpc-url` or the configured
+    /// endpoint, exiting with an error message if there is none or the
+    /// query fails
+    fn query_balance(&self, address: &str) -> Balance {
+        let config = APP.config();
+        // ethereum.rpc defaults to a local node, which is only queried if
+        // it was asked for.
+        let configured = config.source("ethereum.rpc") != Source::Default;
+        let rpc = match &self.rpc_url {
+            Some(rpc_url) => rpc_url.trim().to_string(),
+            None if configured => config.ethereum.rpc.trim().to_string(),
+            None => String::new(),
+        };
+        if rpc.is_empty() {
+            error!("--balance needs an Ethereum RPC endpoint: set ethereum.rpc or pass --rpc-url");
+            process::exit(1);
+        }
+
+        let address: clarity::Address = address.parse().expect("Could not parse address");
+        let web3 = web30::client::Web3::new(&rpc, BALANCE_TIMEOUT);
+        // web30's HTTP client has to run on an actix system.
+        let wei = actix_rt::System::new()
+            .block_on(web3.eth_get_balance(address))
+            .unwrap_or_else(|err| {
+                error!("could not query balance from {}: {}", rpc, err);
+                process::exit(1);
+            })
+            .to_string();
+
+        Balance {
+            ether: format_ether(&wei),
+            wei,
+        }
+    }
 }
 
 // Entry point for `gorc keys eth show [name]`
 impl Runnable for ShowEthKeyCmd {
     fn run(&self) {
-        let config = APP.config();
-        let keystore = path::Path::new(&config.keystore);
-        let keystore = FsKeyStore::create_or_open(keystore).expect("Could not open keystore");
-
         let name = self.args.get(0).expect("name is required");
-        let name = name.parse().expect("Could not parse name");
+        let mut info =
+            Self::try_key_info(name, &self.password_file).unwrap_or_else(|err| err.exit());
+
+        // Network access only happens when asked for.
+        let balance = if self.balance {
+            Some(self.query_balance(&info.address))
+        } else {
+            None
+        };
 
-        let key = keystore.load(&name).expect("Could not load key");
-        let key = key
-            .to_pem()
-            .parse::<k256::elliptic_curve::SecretKey<k256::Secp256k1>>()
-            .expect("Could not parse key");
+        // The QR code always carries the checksummed form.
+        let checksummed = info.address.clone();
+        if self.lowercase {
+            info.address = info.address.to_lowercase();
+        }
 
-        let key = clarity::PrivateKey::from_slice(&key.to_bytes()).expect("Could not convert key");
+        match self.output {
+            OutputFormat::Text => {
+                let balance = balance
+                    .as_ref()
+                    .map(|balance| format!("{} ETH ({} wei)", balance.ether, balance.wei));
+                let mut fields = vec![
+                    ("name", info.name.as_str()),
+                    ("address", &info.address),
+                    ("public_key", &info.public_key),
+                    ("fingerprint", &info.fingerprint),
+                    (
+                        "derivation_path",
+                        info.derivation_path.as_deref().unwrap_or("none"),
+                    ),
+                ];
+                if let Some(balance) = &balance {
+                    fields.push(("balance", balance));
+                }
+                output::print_fields(&fields);
+            }
+            OutputFormat::Json => output::print_versioned_json(&ShownEthKey { info, balance }),
+        }
+
+        if self.qr && self.output == OutputFormat::Text {
+            print_qr(&checksummed);
+        }
+    }
+}
+
+/// Format an amount of wei given in decimal as ether, without trailing
+/// zeros
+fn format_ether(wei: &str) -> String {
+    let padded = format!("{:0>19}", wei);
+    let (ether, fraction) = padded.split_at(padded.len() - 18);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        ether.to_string()
+    } else {
+        format!("{}.{}", ether, fraction)
+    }
+}
 
-        let pub_key = key.to_public_key().expect("Could not build public key");
+/// Print the checksummed address as a QR code, skipped when stdout isn't a
+/// terminal since the blocks only make sense there
+fn print_qr(address: &str) {
+    if !atty::is(atty::Stream::Stdout) {
+        info!("stdout is not a terminal, not printing QR code");
+        return;
+    }
+
+    // The address has been printed as text already, which is enough if it
+    // can't be encoded.
+    match qr::render(address.as_bytes()) {
+        Ok(code) => print!("\n{}", code),
+        Err(err) => warn!("could not encode QR code: {}", err),
+    }
+}
+
+/// EIP-55 checksummed address of the key
+pub fn address(key: &k256::SecretKey) -> String {
+    let key = clarity::PrivateKey::from_slice(&key.to_bytes()).expect("Could not convert key");
+
+    key.to_public_key()
+        .expect("Could not build public key")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{address, format_ether};
+
+    #[test]
+    fn checksums_address() {
+        let key = clarity::utils::hex_str_to_bytes(
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318",
+        )
+        .unwrap();
+        let key = k256::SecretKey::from_bytes(&key).unwrap();
+
+        assert_eq!(address(&key), "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23");
+    }
 
-        println!("{}\t{}", name, pub_key);
+    #[test]
+    fn formats_wei_as_ether() {
+        assert_eq!(format_ether("0"), "0");
+        assert_eq!(format_ether("1"), "0.000000000000000001");
+        assert_eq!(format_ether("1500000000000000000"), "1.5");
+        assert_eq!(format_ether("42000000000000000000"), "42");
     }
 }
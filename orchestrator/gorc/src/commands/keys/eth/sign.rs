@@ -0,0 +1,143 @@
+use crate::{
+    commands::keys::keystore,
+    output::{self, OutputFormat},
+    prelude::*,
+};
+use abscissa_core::{Command, Options, Runnable};
+use clarity::{PrivateKey, Signature};
+use serde::Serialize;
+use sha3::{Digest, Keccak256};
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+    process,
+};
+
+#[derive(Command, Debug, Default, Options)]
+pub struct SignEthKeyCmd {
+    #[options(free, help = "sign [name] [message]")]
+    pub args: Vec<String>,
+
+    #[options(help = "read the message from a file")]
+    pub message_file: Option<PathBuf>,
+
+    #[options(short = "o", help = "output format (text|json)")]
+    pub output: OutputFormat,
+
+    #[options(help = "read the keystore password from a file")]
+    pub password_file: Option<PathBuf>,
+}
+
+/// Signature over a message and the address which made it
+#[derive(Debug, Serialize)]
+pub struct SignedMessage {
+    pub address: String,
+    pub signature: String,
+}
+
+// Entry point for `gorc keys eth sign [name] [message]`
+// - [name] required; key name
+// - [message] 0x-prefixed hex, text, or `-` to read from stdin; may be
+//   omitted when --message-file is given
+impl Runnable for SignEthKeyCmd {
+    fn run(&self) {
+        let name = self.args.get(0).expect("name is required");
+        let name = keystore::key_name(name);
+        if !keystore::key_exists(&name) {
+            error!("key '{}' does not exist", name);
+            process::exit(1);
+        }
+        let message = read_message(self.args.get(1).map(String::as_str), &self.message_file);
+
+        let key = keystore::load_key(&name, &self.password_file);
+        let key = key
+            .to_pem()
+            .parse::<k256::elliptic_curve::SecretKey<k256::Secp256k1>>()
+            .expect("Could not parse key");
+        let key = PrivateKey::from_slice(&key.to_bytes()).expect("Could not convert key");
+
+        let signed = SignedMessage {
+            address: key
+                .to_public_key()
+                .expect("Could not build public key")
+                .to_string(),
+            signature: sign_message(&key, &message).to_string(),
+        };
+
+        match self.output {
+            OutputFormat::Text => println!("{}\t{}", signed.address, signed.signature),
+            OutputFormat::Json => output::print_json(&signed),
+        }
+    }
+}
+
+/// Hash a message the way EIP-191 `personal_sign` does:
+/// `keccak256("\x19Ethereum Signed Message:\n" + len(message) + message)`
+pub fn hash_message(message: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(format!("\x19Ethereum Signed Message:\n{}", message.len()));
+    hasher.update(message);
+    hasher.finalize().into()
+}
+
+/// Produce an EIP-191 `personal_sign` signature over the message
+pub fn sign_message(key: &PrivateKey, message: &[u8]) -> Signature {
+    key.sign_hash(&hash_message(message))
+}
+
+/// Read the message to sign or verify.
+///
+/// The message is taken from `--message-file` if given, otherwise from the
+/// argument: `-` reads stdin, 0x-prefixed hex is decoded and anything else is
+/// used as text. Exits with an error message if no message was supplied.
+pub fn read_message(arg: Option<&str>, message_file: &Option<PathBuf>) -> Vec<u8> {
+    if let Some(path) = message_file {
+        return fs::read(path).expect("Could not read message file");
+    }
+
+    match arg {
+        Some("-") => {
+            let mut message = Vec::new();
+            io::stdin()
+                .read_to_end(&mut message)
+                .expect("Could not read stdin");
+            message
+        }
+        Some(message) => match message.strip_prefix("0x") {
+            Some(hex) => clarity::utils::hex_str_to_bytes(hex).unwrap_or_else(|_| {
+                error!("message is not valid hex");
+                process::exit(1);
+            }),
+            None => message.as_bytes().to_vec(),
+        },
+        None => {
+            error!("a message or --message-file is required");
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hash_message, sign_message};
+    use clarity::PrivateKey;
+
+    // Vector from the web3.js `eth.accounts.sign` documentation
+    const KEY: &str = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    #[test]
+    fn signs_personal_message() {
+        let key: PrivateKey = KEY.parse().unwrap();
+
+        assert_eq!(
+            clarity::utils::bytes_to_hex_str(&hash_message(b"Some data")),
+            "1da44b586eb0729ff70a73c326926f6ed5a25f5b056e7f47fbc6e58d86871655"
+        );
+        assert_eq!(
+            sign_message(&key, b"Some data").to_string(),
+            "0xb91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd\
+             6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c"
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use super::sign::{hash_message, read_message};
+use crate::prelude::*;
+use abscissa_core::{Command, Options, Runnable};
+use clarity::{Address, Signature};
+use std::{path::PathBuf, process};
+
+#[derive(Command, Debug, Default, Options)]
+pub struct VerifyEthKeyCmd {
+    #[options(free, help = "verify [address] [message] [signature]")]
+    pub args: Vec<String>,
+
+    #[options(help = "read the message from a file")]
+    pub message_file: Option<PathBuf>,
+}
+
+/// Why a signature failed to verify
+#[derive(Debug, PartialEq)]
+pub enum VerifyError {
+    /// The signature couldn't be parsed or no signer could be recovered
+    Malformed(String),
+
+    /// The signature was made by a different address
+    Mismatch(Address),
+}
+
+// Entry point for `gorc keys eth verify [address] [message] [signature]`
+// - [address] required; expected signer
+// - [message] 0x-prefixed hex, text, or `-` to read from stdin; omitted
+//   when --message-file is given
+// - [signature] required; 0x-prefixed hex
+//
+// Exits non-zero unless the signature was made by [address]. No keystore
+// access is needed.
+impl Runnable for VerifyEthKeyCmd {
+    fn run(&self) {
+        let address = self.args.get(0).map(String::as_str).unwrap_or_default();
+        // clarity parses an empty string as the zero address, which would
+        // make a missing argument look like a mismatched signer.
+        if address.is_empty() {
+            error!("address is required");
+            process::exit(1);
+        }
+        let address = address.parse::<Address>().unwrap_or_else(|_| {
+            error!("invalid address '{}'", address);
+            process::exit(1);
+        });
+
+        let (message, signature) = match &self.message_file {
+            Some(_) => (read_message(None, &self.message_file), self.args.get(1)),
+            None => (
+                read_message(self.args.get(1).map(String::as_str), &None),
+                self.args.get(2),
+            ),
+        };
+        let signature = signature.unwrap_or_else(|| {
+            error!("signature is required");
+            process::exit(1);
+        });
+
+        match verify_message(&address, &message, signature) {
+            Ok(()) => info!("signature is valid for {}", address),
+            Err(VerifyError::Mismatch(recovered)) => {
+                error!("signature was made by {}, not {}", recovered, address);
+                println!("{}", recovered);
+                process::exit(1);
+            }
+            Err(VerifyError::Malformed(reason)) => {
+                error!("invalid signature: {}", reason);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// Check that the EIP-191 `personal_sign` signature over the message was
+/// made by the given address
+pub fn verify_message(
+    address: &Address,
+    message: &[u8],
+    signature: &str,
+) -> Result<(), VerifyError> {
+    let signature = parse_signature(signature).map_err(VerifyError::Malformed)?;
+
+    let recovered = signature
+        .recover(&hash_message(message))
+        .map_err(|err| VerifyError::Malformed(err.to_string()))?;
+
+    if &recovered == address {
+        Ok(())
+    } else {
+        Err(VerifyError::Mismatch(recovered))
+    }
+}
+
+/// Parse a 65 byte `r || s || v` signature.
+///
+/// Some signers encode the recovery id `v` as 0/1 rather than 27/28; both are
+/// accepted. Anything else is rejected up front since clarity would treat it
+/// as an EIP-155 chain id.
+fn parse_signature(signature: &str) -> Result<Signature, String> {
+    let hex = signature.strip_prefix("0x").unwrap_or(signature);
+    let mut bytes =
+        clarity::utils::hex_str_to_bytes(hex).map_err(|_| "not valid hex".to_string())?;
+    if bytes.len() != 65 {
+        return Err(format!("expected 65 bytes, got {}", bytes.len()));
+    }
+
+    bytes[64] = match bytes[64] {
+        v @ 0..=1 => v + 27,
+        v @ 27..=28 => v,
+        v => return Err(format!("invalid recovery id {}", v)),
+    };
+
+    Signature::from_bytes(&bytes).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{verify_message, VerifyError};
+    use clarity::Address;
+
+    const ADDRESS: &str = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23";
+    const SIGNATURE: &str = "0xb91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd\
+                             6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c";
+
+    #[test]
+    fn accepts_valid_signature() {
+        let address: Address = ADDRESS.parse().unwrap();
+        assert_eq!(verify_message(&address, b"Some data", SIGNATURE), Ok(()));
+    }
+
+    #[test]
+    fn rejects_tampered_message() {
+        let address: Address = ADDRESS.parse().unwrap();
+        match verify_message(&address, b"Some date", SIGNATURE) {
+            Err(VerifyError::Mismatch(recovered)) => assert_ne!(recovered, address),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_signature() {
+        let address: Address = ADDRESS.parse().unwrap();
+        let truncated = &SIGNATURE[..SIGNATURE.len() - 2];
+        assert!(matches!(
+            verify_message(&address, b"Some data", truncated),
+            Err(VerifyError::Malformed(_))
+        ));
+        assert!(matches!(
+            verify_message(&address, b"Some data", "0xnothex"),
+            Err(VerifyError::Malformed(_))
+        ));
+
+        let bad_recovery_id = format!("{}ff", truncated);
+        assert!(matches!(
+            verify_message(&address, b"Some data", &bad_recovery_id),
+            Err(VerifyError::Malformed(_))
+        ));
+    }
+}
@@ -0,0 +1,271 @@
+//! Storage backends holding the contents of key files.
+//!
+//! The keystore helpers encrypt, decrypt and parse keys; a backend only
+//! keeps the PEM or JSON documents they produce under the key's name.
+
+use super::{
+    ensure_parent_dir, json, json_key_path, key_file_name, key_files, key_path,
+    remove_file_if_exists, stored_key_path, write_secret_file, KeyName,
+};
+use crate::error::{Error, ErrorKind, KeysError};
+use keyring::Entry;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Service the OS backend stores keys under
+pub const OS_SERVICE: &str = "gorc";
+
+/// Storage for the contents of key files
+pub trait Backend {
+    /// Names of all stored keys, sorted, including namespaced ones with
+    /// `recursive`. Entries whose name can't be used are returned as errors
+    /// after the names.
+    fn names(&self, recursive: bool) -> Result<Vec<Result<KeyName, KeysError>>, KeysError>;
+
+    /// Whether a key is stored under the name
+    fn exists(&self, name: &KeyName) -> Result<bool, Error>;
+
+    /// Contents of the named key
+    fn load(&self, name: &KeyName) -> Result<String, Error>;
+
+    /// Store the contents of a key, replacing any key of the same name
+    fn store(&self, name: &KeyName, contents: &str) -> Result<(), Error>;
+
+    /// Delete the named key
+    fn delete(&self, name: &KeyName) -> Result<(), Error>;
+
+    /// Where the named key is kept, for messages
+    fn location(&self, name: &KeyName) -> String;
+
+    /// When the named key was last written, `None` if the backend doesn't
+    /// record it
+    fn modified(&self, name: &KeyName) -> Option<SystemTime>;
+}
+
+/// Key files in the keystore directory: `<name>.pem` for PKCS#8 keys and
+/// `<name>.json` for JSON keystores
+#[derive(Debug, Default)]
+pub struct FileBackend;
+
+impl Backend for FileBackend {
+    fn names(&self, recursive: bool) -> Result<Vec<Result<KeyName, KeysError>>, KeysError> {
+        let mut names = Vec::new();
+        let mut errors = Vec::new();
+        for path in key_files(recursive)? {
+            match key_file_name(&path) {
+                Ok(name) => names.push(name),
+                Err(err) => errors.push(err),
+            }
+        }
+        names.sort();
+        names.dedup();
+
+        Ok(names
+            .into_iter()
+            .map(Ok)
+            .chain(errors.into_iter().map(Err))
+            .collect())
+    }
+
+    fn exists(&self, name: &KeyName) -> Result<bool, Error> {
+        Ok(key_path(name).exists() || json_key_path(name).exists())
+    }
+
+    fn load(&self, name: &KeyName) -> Result<String, Error> {
+        Ok(fs::read_to_string(stored_key_path(name))?)
+    }
+
+    fn store(&self, name: &KeyName, contents: &str) -> Result<(), Error> {
+        let (path, other_path) = if json::is_json(contents) {
+            (json_key_path(name), key_path(name))
+        } else {
+            (key_path(name), json_key_path(name))
+        };
+
+        ensure_parent_dir(&path)?;
+        write_secret_file(&path, contents.as_bytes())?;
+        // Don't leave a copy in the other format around to shadow the key.
+        remove_file_if_exists(&other_path)
+    }
+
+    fn delete(&self, name: &KeyName) -> Result<(), Error> {
+        fs::remove_file(stored_key_path(name))?;
+        Ok(())
+    }
+
+    fn location(&self, name: &KeyName) -> String {
+        stored_key_path(name).display().to_string()
+    }
+
+    fn modified(&self, name: &KeyName) -> Option<SystemTime> {
+        fs::metadata(stored_key_path(name))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+}
+
+/// The operating system's keyring: the Secret Service on Linux, Keychain
+/// on macOS and the Credential Manager on Windows.
+///
+/// Keys are stored as entries of the `gorc` service with the key name as
+/// the username. The `keyring` crate can't search the keyring, so instead of
+/// enumerating the `gorc` service `names` reads an index file in the
+/// keystore directory, one name per line, which `store` and `delete` keep up
+/// to date. Entries added or removed outside gorc, or a lost index, make the
+/// listing drift from the keyring; a key missing from the index is still
+/// found by name.
+#[derive(Debug)]
+pub struct OsBackend {
+    index: PathBuf,
+}
+
+impl OsBackend {
+    /// Backend keeping its index of names in the given file
+    pub fn new(index: PathBuf) -> Self {
+        OsBackend { index }
+    }
+
+    fn entry(name: &KeyName) -> Entry {
+        Entry::new(OS_SERVICE, name)
+    }
+
+    /// Names in the index, as written
+    fn indexed(&self) -> Result<Vec<String>, Error> {
+        match fs::read_to_string(&self.index) {
+            Ok(index) => Ok(index.lines().map(String::from).collect()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Add the name to the index, or remove it with `!present`
+    fn update_index(&self, name: &KeyName, present: bool) -> Result<(), Error> {
+        let mut names = self.indexed()?;
+        names.retain(|indexed| indexed != &**name);
+        if present {
+            names.push(name.to_string());
+        }
+        names.sort();
+
+        let mut index = names.join("\n");
+        if !index.is_empty() {
+            index.push('\n');
+        }
+        write_secret_file(&self.index, index.as_bytes())
+    }
+}
+
+/// Error for a failed keyring operation
+fn keyring_error(action: &str, name: &KeyName, err: keyring::Error) -> Error {
+    let reason = match err {
+        keyring::Error::NoEntry => format!("key '{}' not found in the os keyring", name),
+        err => format!(
+            "could not {} key '{}' in the os keyring: {}",
+            action, name, err
+        ),
+    };
+    ErrorKind::Key.context(reason).into()
+}
+
+impl Backend for OsBackend {
+    fn names(&self, recursive: bool) -> Result<Vec<Result<KeyName, KeysError>>, KeysError> {
+        let mut names = Vec::new();
+        let mut errors = Vec::new();
+        for name in self.indexed()? {
+            match name.parse::<KeyName>() {
+                Ok(name) if name.namespace().is_some() && !recursive => {}
+                Ok(name) => names.push(name),
+                Err(_) => errors.push(KeysError::InvalidName {
+                    path: Path::new(OS_SERVICE).join(name),
+                }),
+            }
+        }
+        names.sort();
+        names.dedup();
+
+        Ok(names
+            .into_iter()
+            .map(Ok)
+            .chain(errors.into_iter().map(Err))
+            .collect())
+    }
+
+    fn exists(&self, name: &KeyName) -> Result<bool, Error> {
+        if self.indexed()?.iter().any(|indexed| indexed == &**name) {
+            return Ok(true);
+        }
+        // Entries stored by other tools aren't in the index.
+        Ok(Self::entry(name).get_password().is_ok())
+    }
+
+    fn load(&self, name: &KeyName) -> Result<String, Error> {
+        Self::entry(name)
+            .get_password()
+            .map_err(|err| keyring_error("load", name, err))
+    }
+
+    fn store(&self, name: &KeyName, contents: &str) -> Result<(), Error> {
+        Self::entry(name)
+            .set_password(contents)
+            .map_err(|err| keyring_error("store", name, err))?;
+        self.update_index(name, true)
+    }
+
+    fn delete(&self, name: &KeyName) -> Result<(), Error> {
+        match Self::entry(name).delete_password() {
+            // An entry removed from the keyring by other means only needs
+            // dropping from the index.
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(err) => return Err(keyring_error("delete", name, err)),
+        }
+        self.update_index(name, false)
+    }
+
+    fn location(&self, name: &KeyName) -> String {
+        format!("os keyring ({}/{})", OS_SERVICE, name)
+    }
+
+    fn modified(&self, _name: &KeyName) -> Option<SystemTime> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Backend, OsBackend};
+    use std::fs;
+
+    #[test]
+    fn lists_indexed_os_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = OsBackend::new(dir.path().join("os-keyring.index"));
+        assert!(backend.names(true).unwrap().is_empty());
+
+        for name in &["validator1", "gravity/orchestrator", "validator1", "alice"] {
+            backend.update_index(&name.parse().unwrap(), true).unwrap();
+        }
+        backend
+            .update_index(&"alice".parse().unwrap(), false)
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("os-keyring.index")).unwrap(),
+            "gravity/orchestrator\nvalidator1\n"
+        );
+
+        let names = |recursive| -> Vec<String> {
+            backend
+                .names(recursive)
+                .unwrap()
+                .into_iter()
+                .map(|name| name.unwrap().to_string())
+                .collect()
+        };
+        assert_eq!(names(false), ["validator1"]);
+        assert_eq!(names(true), ["gravity/orchestrator", "validator1"]);
+        assert!(backend.exists(&"validator1".parse().unwrap()).unwrap());
+        assert!(!backend.exists(&"alice".parse().unwrap()).unwrap());
+    }
+}
@@ -0,0 +1,282 @@
+//! Web3 Secret Storage (version 3 JSON keystore) files as written by geth
+//! and ethers.
+//!
+//! See <https://github.com/ethereum/wiki/wiki/Web3-Secret-Storage-Definition>
+
+use aes::Aes128;
+use ctr::cipher::{NewCipher, StreamCipher};
+use hmac::Hmac;
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+
+/// AES-128 in CTR mode with a big-endian 128-bit counter
+type Aes128Ctr = ctr::Ctr128BE<Aes128>;
+
+/// Length of the derived key; the first half encrypts, the second half
+/// authenticates the ciphertext
+const DERIVED_KEY_LEN: usize = 32;
+
+#[derive(Debug, Deserialize, Serialize)]
+struct KeystoreFile {
+    version: u32,
+    id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    #[serde(alias = "Crypto")]
+    crypto: Crypto,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Crypto {
+    cipher: String,
+    cipherparams: CipherParams,
+    ciphertext: String,
+    kdf: String,
+    kdfparams: serde_json::Value,
+    mac: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CipherParams {
+    iv: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ScryptParams {
+    dklen: usize,
+    n: u32,
+    r: u32,
+    p: u32,
+    salt: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pbkdf2Params {
+    dklen: usize,
+    c: u32,
+    prf: String,
+    salt: String,
+}
+
+/// Whether the contents look like a JSON keystore rather than PEM or a bare
+/// secret
+pub fn is_json(contents: &str) -> bool {
+    contents.trim_start().starts_with('{')
+}
+
+/// EIP-55 checksummed address recorded in a JSON keystore, `None` if it
+/// has none or isn't a keystore
+pub fn address(contents: &str) -> Option<String> {
+    let file: KeystoreFile = serde_json::from_str(contents).ok()?;
+    let address: clarity::Address = file.address?.parse().ok()?;
+    Some(address.to_string())
+}
+
+/// Decrypt a JSON keystore with the given password
+pub fn decrypt(contents: &str, password: &str) -> Result<k256::SecretKey, String> {
+    let file: KeystoreFile =
+        serde_json::from_str(contents).map_err(|err| format!("invalid JSON keystore: {}", err))?;
+    if file.version != 3 {
+        return Err(format!("unsupported keystore version {}", file.version));
+    }
+
+    let crypto = file.crypto;
+    if crypto.cipher != "aes-128-ctr" {
+        return Err(format!("unsupported cipher '{}'", crypto.cipher));
+    }
+
+    let derived_key = match crypto.kdf.as_str() {
+        "scrypt" => {
+            let params: ScryptParams = serde_json::from_value(crypto.kdfparams)
+                .map_err(|err| format!("invalid scrypt parameters: {}", err))?;
+            if params.dklen < DERIVED_KEY_LEN || !params.n.is_power_of_two() {
+                return Err("invalid scrypt parameters".to_string());
+            }
+
+            let log_n = params.n.trailing_zeros() as u8;
+            let scrypt_params = scrypt::Params::new(log_n, params.r, params.p)
+                .map_err(|_| "invalid scrypt parameters".to_string())?;
+            let mut derived_key = vec![0u8; params.dklen];
+            scrypt::scrypt(
+                password.as_bytes(),
+                &hex(&params.salt)?,
+                &scrypt_params,
+                &mut derived_key,
+            )
+            .map_err(|_| "invalid scrypt parameters".to_string())?;
+            derived_key
+        }
+        "pbkdf2" => {
+            let params: Pbkdf2Params = serde_json::from_value(crypto.kdfparams)
+                .map_err(|err| format!("invalid pbkdf2 parameters: {}", err))?;
+            if params.prf != "hmac-sha256" {
+                return Err(format!("unsupported pbkdf2 prf '{}'", params.prf));
+            }
+            if params.dklen < DERIVED_KEY_LEN {
+                return Err("invalid pbkdf2 parameters".to_string());
+            }
+
+            let mut derived_key = vec![0u8; params.dklen];
+            pbkdf2::pbkdf2::<Hmac<Sha256>>(
+                password.as_bytes(),
+                &hex(&params.salt)?,
+                params.c,
+                &mut derived_key,
+            );
+            derived_key
+        }
+        kdf => return Err(format!("unsupported kdf '{}'", kdf)),
+    };
+
+    let mut ciphertext = hex(&crypto.ciphertext)?;
+    if mac(&derived_key, &ciphertext) != hex(&crypto.mac)? {
+        return Err("wrong password or corrupt keystore".to_string());
+    }
+
+    aes_128_ctr(
+        &derived_key[..16],
+        &hex(&crypto.cipherparams.iv)?,
+        &mut ciphertext,
+    )?;
+    k256::SecretKey::from_bytes(&ciphertext).map_err(|_| "invalid private key".to_string())
+}
+
+/// Encrypt the key as a JSON keystore using scrypt with the given parameters
+pub fn encrypt(key: &k256::SecretKey, password: &str, params: scrypt::Params) -> String {
+    let mut salt = [0u8; 32];
+    OsRng.fill_bytes(&mut salt);
+
+    let mut iv = [0u8; 16];
+    OsRng.fill_bytes(&mut iv);
+
+    let mut derived_key = [0u8; DERIVED_KEY_LEN];
+    scrypt::scrypt(password.as_bytes(), &salt, &params, &mut derived_key)
+        .expect("Could not derive encryption key");
+
+    let mut ciphertext = key.to_bytes().to_vec();
+    aes_128_ctr(&derived_key[..16], &iv, &mut ciphertext).expect("Could not encrypt key");
+
+    let address = clarity::PrivateKey::from_slice(&key.to_bytes())
+        .and_then(|key| key.to_public_key())
+        .expect("Could not build public key");
+
+    let file = KeystoreFile {
+        version: 3,
+        id: uuid_v4(),
+        address: Some(clarity::utils::bytes_to_hex_str(address.as_bytes())),
+        crypto: Crypto {
+            cipher: "aes-128-ctr".to_string(),
+            cipherparams: CipherParams {
+                iv: clarity::utils::bytes_to_hex_str(&iv),
+            },
+            ciphertext: clarity::utils::bytes_to_hex_str(&ciphertext),
+            kdf: "scrypt".to_string(),
+            kdfparams: serde_json::to_value(ScryptParams {
+                dklen: DERIVED_KEY_LEN,
+                n: 1 << params.log_n(),
+                r: params.r(),
+                p: params.p(),
+                salt: clarity::utils::bytes_to_hex_str(&salt),
+            })
+            .expect("Could not serialize scrypt parameters"),
+            mac: clarity::utils::bytes_to_hex_str(&mac(&derived_key, &ciphertext)),
+        },
+    };
+
+    serde_json::to_string_pretty(&file).expect("Could not serialize keystore")
+}
+
+/// Keccak-256 of the second half of the derived key and the ciphertext
+fn mac(derived_key: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    Keccak256::new()
+        .chain(&derived_key[16..32])
+        .chain(ciphertext)
+        .finalize()
+        .to_vec()
+}
+
+/// Encrypt or decrypt the data in place with AES-128 in CTR mode, starting
+/// the counter at the IV
+fn aes_128_ctr(key: &[u8], iv: &[u8], data: &mut [u8]) -> Result<(), String> {
+    let mut cipher =
+        Aes128Ctr::new_from_slices(key, iv).map_err(|_| "invalid cipher iv".to_string())?;
+    cipher.apply_keystream(data);
+    Ok(())
+}
+
+/// Decode a hex field, with or without a 0x prefix
+fn hex(s: &str) -> Result<Vec<u8>, String> {
+    clarity::utils::hex_str_to_bytes(s).map_err(|_| format!("invalid hex '{}'", s))
+}
+
+/// Random (version 4) UUID identifying the keystore file
+fn uuid_v4() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex = clarity::utils::bytes_to_hex_str(&bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{address, decrypt, encrypt};
+
+    /// Test vector from the Web3 Secret Storage definition
+    const PBKDF2_KEYSTORE: &str = r#"{
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": { "iv": "6087dab2f9fdbbfaddc31a909735c1e6" },
+            "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+            "kdf": "pbkdf2",
+            "kdfparams": {
+                "c": 262144,
+                "dklen": 32,
+                "prf": "hmac-sha256",
+                "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+            },
+            "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3
+    }"#;
+
+    #[test]
+    fn decrypts_reference_keystore() {
+        let key = decrypt(PBKDF2_KEYSTORE, "testpassword").unwrap();
+        assert_eq!(
+            clarity::utils::bytes_to_hex_str(&key.to_bytes()),
+            "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d"
+        );
+
+        assert!(decrypt(PBKDF2_KEYSTORE, "wrong").is_err());
+        assert_eq!(address(PBKDF2_KEYSTORE), None);
+    }
+
+    #[test]
+    fn round_trips_encrypted_keystore() {
+        let key = k256::SecretKey::from_bytes(&[1u8; 32]).unwrap();
+        let params = scrypt::Params::new(10, 8, 1).unwrap();
+
+        let contents = encrypt(&key, "hunter2", params);
+        let decrypted = decrypt(&contents, "hunter2").unwrap();
+        assert_eq!(decrypted.to_bytes(), key.to_bytes());
+
+        // The address is readable without the password.
+        let expected = clarity::PrivateKey::from_slice(&[1u8; 32])
+            .and_then(|key| key.to_public_key())
+            .unwrap();
+        assert_eq!(address(&contents), Some(expected.to_string()));
+    }
+}
@@ -0,0 +1,95 @@
+//! Names of keys in the keystore
+
+use std::{
+    fmt::{self, Display},
+    ops::Deref,
+    path::Path,
+    str::FromStr,
+};
+use thiserror::Error;
+
+/// Name of a key: letters, numbers, `-` and `_`, optionally namespaced by a
+/// single subdirectory of the keystore, e.g. `gravity/validator1`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct KeyName(String);
+
+/// A name which isn't made of allowed characters and at most one namespace
+#[derive(Clone, Copy, Debug, Eq, Error, PartialEq)]
+#[error("invalid key name")]
+pub struct InvalidKeyName;
+
+impl KeyName {
+    /// Create a new key name from the given string.
+    pub fn new(name: impl Into<String>) -> Result<Self, InvalidKeyName> {
+        let name = name.into();
+
+        let segment_valid = |segment: &str| {
+            !segment.is_empty()
+                && segment.bytes().all(
+                    |byte| matches!(byte, b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_'),
+                )
+        };
+        if name.split('/').count() > 2 || !name.split('/').all(segment_valid) {
+            return Err(InvalidKeyName);
+        }
+
+        Ok(Self(name))
+    }
+
+    /// Subdirectory of the keystore the key is kept in, if any
+    pub fn namespace(&self) -> Option<&str> {
+        self.0.split_once('/').map(|(namespace, _)| namespace)
+    }
+}
+
+impl FromStr for KeyName {
+    type Err = InvalidKeyName;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::new(name)
+    }
+}
+
+impl AsRef<str> for KeyName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for KeyName {
+    fn as_ref(&self) -> &Path {
+        self.0.as_ref()
+    }
+}
+
+impl Deref for KeyName {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for KeyName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeyName;
+
+    #[test]
+    fn allows_one_namespace() {
+        assert_eq!(KeyName::new("validator1").unwrap().namespace(), None);
+        let name = KeyName::new("gravity/validator1").unwrap();
+        assert_eq!(name.namespace(), Some("gravity"));
+
+        assert!(KeyName::new("a/b/c").is_err());
+        assert!(KeyName::new("/validator1").is_err());
+        assert!(KeyName::new("gravity/").is_err());
+        assert!(KeyName::new("../validator1").is_err());
+        assert!(KeyName::new("gravity.validator1").is_err());
+    }
+}
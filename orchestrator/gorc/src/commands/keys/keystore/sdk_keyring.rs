@@ -0,0 +1,317 @@
+//! Cosmos SDK `keyring-file` directories, as written by e.g.
+//! `gravity keys add --keyring-backend file`.
+//!
+//! Each key is a `<name>.info` file holding a JWE (RFC 7516) in compact
+//! serialization, encrypted with PBES2-HS256+A128KW and A256GCM under the
+//! keyring password. The payload is a JSON keyring item whose data is the
+//! key's length-prefixed amino `LocalInfo`, or its protobuf `Record` from
+//! SDK v0.46 on.
+
+use aes_gcm::{
+    aead::{AeadInPlace, NewAead},
+    Aes256Gcm, Nonce, Tag,
+};
+use aes_kw::KekAes128;
+use hmac::Hmac;
+use serde::Deserialize;
+use sha2::Sha256;
+use std::{fs, path::Path};
+
+/// Amino prefix of `tendermint/PrivKeySecp256k1`
+const AMINO_PRIV_KEY_PREFIX: [u8; 4] = [0xe1, 0xb0, 0xf7, 0x9b];
+
+/// Amino prefix of `tendermint/PubKeySecp256k1`
+const AMINO_PUB_KEY_PREFIX: [u8; 4] = [0xeb, 0x5a, 0xe9, 0x87];
+
+const PRIV_KEY_TYPE_URL: &str = "/cosmos.crypto.secp256k1.PrivKey";
+const PUB_KEY_TYPE_URL: &str = "/cosmos.crypto.secp256k1.PubKey";
+
+/// Key read from a keyring-file directory
+#[derive(Debug)]
+pub struct KeyringKey {
+    pub key: k256::SecretKey,
+    /// Compressed SEC1 public key the SDK stored alongside the private key,
+    /// which its reported address is derived from
+    pub public_key: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JweHeader {
+    alg: String,
+    enc: String,
+    p2s: String,
+    p2c: u32,
+    #[serde(default)]
+    zip: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyringItem {
+    #[serde(rename = "Data")]
+    data: String,
+}
+
+/// Read the named key from a keyring-file directory, decrypting it with the
+/// keyring password. `dir` may also be the node's home directory containing
+/// `keyring-file`.
+pub fn read_key(dir: &Path, name: &str, password: &str) -> Result<KeyringKey, String> {
+    let keyring_dir = dir.join("keyring-file");
+    let dir = if keyring_dir.is_dir() {
+        &keyring_dir
+    } else {
+        dir
+    };
+
+    let path = dir.join(format!("{}.info", name));
+    let token = fs::read_to_string(&path)
+        .map_err(|err| format!("could not read {}: {}", path.display(), err))?;
+
+    let payload = decrypt_jwe(token.trim(), password)?;
+    let item: KeyringItem =
+        serde_json::from_slice(&payload).map_err(|err| format!("invalid keyring item: {}", err))?;
+    let info =
+        base64::decode(&item.data).map_err(|err| format!("invalid keyring item: {}", err))?;
+    decode_info(&info)
+}
+
+/// Decrypt a compact serialization JWE encrypted with PBES2-HS256+A128KW
+/// and A256GCM
+fn decrypt_jwe(token: &str, password: &str) -> Result<Vec<u8>, String> {
+    let parts: Vec<&str> = token.split('.').collect();
+    let (encoded_header, encrypted_key, iv, ciphertext, tag) = match parts[..] {
+        [header, key, iv, ciphertext, tag] => (
+            header,
+            base64_url(key)?,
+            base64_url(iv)?,
+            base64_url(ciphertext)?,
+            base64_url(tag)?,
+        ),
+        _ => return Err("not a JWE token".to_string()),
+    };
+
+    let header: JweHeader = serde_json::from_slice(&base64_url(encoded_header)?)
+        .map_err(|err| format!("invalid JWE header: {}", err))?;
+    if header.alg != "PBES2-HS256+A128KW" {
+        return Err(format!("unsupported key encryption '{}'", header.alg));
+    }
+    if header.enc != "A256GCM" {
+        return Err(format!("unsupported content encryption '{}'", header.enc));
+    }
+    if let Some(zip) = header.zip {
+        return Err(format!("unsupported compression '{}'", zip));
+    }
+    if header.p2c == 0 {
+        return Err("invalid PBES2 iteration count".to_string());
+    }
+
+    // The salt is the algorithm name and the header's salt input, separated
+    // by a zero byte (RFC 7518 section 4.8.1.1).
+    let mut salt = header.alg.into_bytes();
+    salt.push(0);
+    salt.extend(base64_url(&header.p2s)?);
+
+    let mut kek = [0u8; 16];
+    pbkdf2::pbkdf2::<Hmac<Sha256>>(password.as_bytes(), &salt, header.p2c, &mut kek);
+
+    let wrong_password = || "wrong password or corrupt keyring".to_string();
+    let cek = KekAes128::from(kek)
+        .unwrap_vec(&encrypted_key)
+        .map_err(|_| wrong_password())?;
+    let cipher = Aes256Gcm::new_from_slice(&cek).map_err(|_| "invalid content encryption key")?;
+
+    // A256GCM uses a 96-bit IV and a 128-bit tag (RFC 7518 section 5.3).
+    if iv.len() != 12 {
+        return Err("invalid JWE iv".to_string());
+    }
+    if tag.len() != 16 {
+        return Err(wrong_password());
+    }
+    let mut payload = ciphertext;
+    cipher
+        .decrypt_in_place_detached(
+            Nonce::from_slice(&iv),
+            encoded_header.as_bytes(),
+            &mut payload,
+            Tag::from_slice(&tag),
+        )
+        .map_err(|_| wrong_password())?;
+    Ok(payload)
+}
+
+/// Decode the private and public key of a keyring entry: a length-prefixed
+/// amino `LocalInfo` or a protobuf `Record`
+fn decode_info(data: &[u8]) -> Result<KeyringKey, String> {
+    let mut rest = data;
+    let amino = matches!(varint(&mut rest), Ok(len) if len == rest.len() as u64 && len > 4);
+    let (priv_key, pub_key) = if amino {
+        // Skip the amino prefix of the concrete info type.
+        decode_local_info(&rest[4..])?
+    } else {
+        decode_record(data)?
+    };
+
+    let key = k256::SecretKey::from_bytes(priv_key)
+        .map_err(|_| "invalid secp256k1 private key".to_string())?;
+    Ok(KeyringKey {
+        key,
+        public_key: pub_key.to_vec(),
+    })
+}
+
+/// Keys of an amino `LocalInfo`: name (1), public key (2), private key (3)
+/// and algorithm (4)
+fn decode_local_info(data: &[u8]) -> Result<(&[u8], &[u8]), String> {
+    let fields = fields(data)?;
+    let priv_key = field(&fields, 3).ok_or_else(no_private_key)?;
+    let pub_key = field(&fields, 2).ok_or_else(|| "keyring entry has no public key".to_string())?;
+
+    Ok((
+        amino_bytes(priv_key, AMINO_PRIV_KEY_PREFIX)?,
+        amino_bytes(pub_key, AMINO_PUB_KEY_PREFIX)?,
+    ))
+}
+
+/// Keys of a protobuf `Record`: name (1), public key `Any` (2) and the
+/// `Local` item (3) holding the private key `Any` (1)
+fn decode_record(data: &[u8]) -> Result<(&[u8], &[u8]), String> {
+    let fields = fields(data)?;
+    let local = field(&fields, 3).ok_or_else(no_private_key)?;
+    let priv_key = field(&self::fields(local)?, 1).ok_or_else(no_private_key)?;
+    let pub_key = field(&fields, 2).ok_or_else(|| "keyring entry has no public key".to_string())?;
+
+    Ok((
+        any_key(priv_key, PRIV_KEY_TYPE_URL)?,
+        any_key(pub_key, PUB_KEY_TYPE_URL)?,
+    ))
+}
+
+fn no_private_key() -> String {
+    "keyring entry has no private key, only local keys can be imported".to_string()
+}
+
+/// The bytes of an amino encoded key of the concrete type with the prefix
+fn amino_bytes(data: &[u8], prefix: [u8; 4]) -> Result<&[u8], String> {
+    let mut rest = data
+        .strip_prefix(&prefix[..])
+        .ok_or_else(|| "unsupported key type, expected secp256k1".to_string())?;
+    match varint(&mut rest) {
+        Ok(len) if len == rest.len() as u64 => Ok(rest),
+        _ => Err("invalid amino encoded key".to_string()),
+    }
+}
+
+/// The `key` field of a protobuf key packed in an `Any` of the given type
+fn any_key<'a>(any: &'a [u8], type_url: &str) -> Result<&'a [u8], String> {
+    let fields = fields(any)?;
+    if field(&fields, 1) != Some(type_url.as_bytes()) {
+        return Err("unsupported key type, expected secp256k1".to_string());
+    }
+
+    let value = field(&fields, 2).unwrap_or_default();
+    field(&self::fields(value)?, 1).ok_or_else(|| "invalid protobuf encoded key".to_string())
+}
+
+/// Length-delimited fields of a protobuf or amino message as
+/// `(field number, contents)`, skipping fields of other wire types
+fn fields(mut data: &[u8]) -> Result<Vec<(u64, &[u8])>, String> {
+    let invalid = || "invalid keyring entry encoding".to_string();
+
+    let mut fields = Vec::new();
+    while !data.is_empty() {
+        let key = varint(&mut data)?;
+        let skip = match key & 7 {
+            0 => {
+                varint(&mut data)?;
+                0
+            }
+            1 => 8,
+            2 => {
+                let len = varint(&mut data)? as usize;
+                if len > data.len() {
+                    return Err(invalid());
+                }
+                fields.push((key >> 3, &data[..len]));
+                len
+            }
+            5 => 4,
+            _ => return Err(invalid()),
+        };
+        data = data.get(skip..).ok_or_else(invalid)?;
+    }
+    Ok(fields)
+}
+
+/// Contents of the first field with the number
+fn field<'a>(fields: &[(u64, &'a [u8])], number: u64) -> Option<&'a [u8]> {
+    fields
+        .iter()
+        .find(|(field, _)| *field == number)
+        .map(|(_, contents)| *contents)
+}
+
+/// Read an unsigned LEB128 varint off the front of `data`
+fn varint(data: &mut &[u8]) -> Result<u64, String> {
+    let mut value = 0u64;
+    for (index, byte) in data.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * index);
+        if byte & 0x80 == 0 {
+            *data = &data[index + 1..];
+            return Ok(value);
+        }
+    }
+    Err("invalid keyring entry encoding".to_string())
+}
+
+/// Decode unpadded base64url, as used by JWE
+fn base64_url(s: &str) -> Result<Vec<u8>, String> {
+    base64::decode_config(s, base64::URL_SAFE_NO_PAD)
+        .map_err(|_| "invalid JWE encoding".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_info, read_key};
+    use std::fs;
+
+    /// `validator` with the private key 0x0101…01, written to a keyring-file
+    /// directory with the password `testpassword`
+    const KEYRING_ENTRY: &str = "eyJhbGciOiJQQkVTMi1IUzI1NitBMTI4S1ciLCJjcmVhdGVkIjoiMjAyMS0xMC0xMyAxNDoyNToyMC4wMDAwMDAwMDAgKzAwMDAgVVRDIiwiZW5jIjoiQTI1NkdDTSIsInAyYyI6ODE5MiwicDJzIjoiREhPRWx5NzRKaVVoV0k0WXdOSHl3USJ9.7bA3dGb_HIkDVv7PAYWXAxGfefpdOdB2ORsGwq67ROy6VEptQLepnQ.XUisx2u9z4dgXjGl.DqXwWeVBb_PIsZK04n2Vlx7yIMjDymFlHKtX_6VtjB1ethesLH8uGYN5V2aQd9l927OvCBkNU-VW1UEuq7n51bV-bRDncPa-vxLQfmz5x4aA1LAor--vhod_S7zosWXO6DpZMxk152VynlsT-XA0Ye6kplTK-zfWxDAw-okjI8JblzT86uVPInyKgpbnxOR6tctvCsiE8tJWIyy4EavTCzRJnSnEcfn-KtHb5BUQrjSN5JmsoRtS4YjqNB2gyAsxg_TWkGL8H9RleLmxpzWetEVXo6g3qurtGcfdG4hO7eECBMINiK3E4JbcnzOQPFTuWWkxEi1jWKeuWOUxbDN_WlQ-okoAJGiDGv1h5NJOkRY_G3Ak4oUmGLVcVaQT.jtON3WVNn8A6h6e8BABARA";
+
+    const PUBLIC_KEY: &str = "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f";
+
+    #[test]
+    fn reads_keyring_file_entry() {
+        let home = tempfile::tempdir().unwrap();
+        let dir = home.path().join("keyring-file");
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("validator.info"), KEYRING_ENTRY).unwrap();
+
+        let key = read_key(home.path(), "validator", "testpassword").unwrap();
+        assert_eq!(key.key.to_bytes().as_slice(), &[1u8; 32][..]);
+        assert_eq!(
+            clarity::utils::bytes_to_hex_str(&key.public_key),
+            PUBLIC_KEY
+        );
+
+        assert!(read_key(&dir, "validator", "wrong").is_err());
+        assert!(read_key(&dir, "missing", "testpassword").is_err());
+    }
+
+    #[test]
+    fn decodes_protobuf_record() {
+        let record = clarity::utils::hex_str_to_bytes(
+            "0a0976616c696461746f7212460a1f2f636f736d6f732e63727970746f2e736563703235366b312e\
+             5075624b657912230a21031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd\
+             078f1a480a460a202f636f736d6f732e63727970746f2e736563703235366b312e507269764b6579\
+             12220a200101010101010101010101010101010101010101010101010101010101010101",
+        )
+        .unwrap();
+
+        let key = decode_info(&record).unwrap();
+        assert_eq!(key.key.to_bytes().as_slice(), &[1u8; 32][..]);
+        assert_eq!(
+            clarity::utils::bytes_to_hex_str(&key.public_key),
+            PUBLIC_KEY
+        );
+    }
+}
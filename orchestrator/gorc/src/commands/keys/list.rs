@@ -0,0 +1,241 @@
+use super::{
+    cosmos::show::ShowCosmosKeyCmd,
+    eth::show::ShowEthKeyCmd,
+    keystore::{self, KeyType},
+};
+use crate::{
+    application::APP,
+    error::KeysError,
+    output::{self, OutputFormat},
+    prelude::*,
+};
+use abscissa_core::{Application, Command, Options, Runnable};
+use serde::Serialize;
+use std::{ops::Not, path::PathBuf, process, str::FromStr};
+
+#[derive(Command, Debug, Default, Options)]
+pub struct ListKeysCmd {
+    #[options(short = "o", help = "output format (text|json)")]
+    pub output: OutputFormat,
+
+    #[options(no_short, help = "order of the keys (name|mtime)")]
+    pub sort_by: SortBy,
+
+    #[options(no_short, help = "also print each key's public key fingerprint")]
+    pub fingerprint: bool,
+
+    #[options(help = "also list keys namespaced in subdirectories of the keystore")]
+    pub recursive: bool,
+
+    #[options(help = "read the keystore password from a file")]
+    pub password_file: Option<PathBuf>,
+
+    #[options(no_short, help = "wrap -o json output in a versioned object")]
+    pub json_schema: bool,
+}
+
+/// Name, type and address of a stored key
+#[derive(Debug, Serialize)]
+pub struct KeyInfo {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub key_type: Option<KeyType>,
+    pub address: Option<String>,
+    /// Fingerprint of the public key, see [`keystore::fingerprint`]
+    pub fingerprint: Option<String>,
+    /// When the key was last written, RFC 3339
+    pub modified: Option<String>,
+    /// Whether the key is encrypted and there was no password to unlock it
+    #[serde(skip_serializing_if = "Not::not")]
+    pub locked: bool,
+}
+
+/// Keys printed by the list commands as JSON with `--json-schema`, see
+/// [`output::print_versioned_json`]
+#[derive(Debug, Serialize)]
+pub struct KeyList<T> {
+    pub keys: Vec<T>,
+}
+
+/// Key details listed along with when the key was last written
+#[derive(Debug, Serialize)]
+pub struct ListedKey<T> {
+    #[serde(flatten)]
+    pub info: T,
+    /// RFC 3339, `None` if the keystore backend doesn't record it
+    pub modified: Option<String>,
+    /// Whether the key is encrypted and there was no password to unlock it
+    #[serde(skip_serializing_if = "Not::not")]
+    pub locked: bool,
+}
+
+/// Shown in place of the details of keys which couldn't be unlocked
+pub const LOCKED: &str = "encrypted (locked)";
+
+/// The cell of a table, `-` if it's empty
+pub fn or_dash(cell: &str) -> &str {
+    if cell.is_empty() {
+        "-"
+    } else {
+        cell
+    }
+}
+
+/// Order keys are listed in
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SortBy {
+    /// Alphabetically by name
+    Name,
+    /// Least recently written first, keys without a modification time last
+    Mtime,
+}
+
+impl Default for SortBy {
+    fn default() -> Self {
+        SortBy::Name
+    }
+}
+
+impl FromStr for SortBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(SortBy::Name),
+            "mtime" => Ok(SortBy::Mtime),
+            _ => Err(format!("unknown sort order '{}', expected name|mtime", s)),
+        }
+    }
+}
+
+impl SortBy {
+    /// Sort keys already in name order, given each key's modification time
+    pub fn sort<T>(self, keys: &mut [T], modified: impl Fn(&T) -> Option<&str>) {
+        if self == SortBy::Mtime {
+            // RFC 3339 UTC timestamps sort chronologically as strings; the
+            // stable sort keeps keys written in the same second by name.
+            keys.sort_by(|a, b| {
+                let (a, b) = (modified(a), modified(b));
+                (a.is_none(), a).cmp(&(b.is_none(), b))
+            });
+        }
+    }
+}
+
+// Entry point for `gorc keys list`
+//
+// Keys created before their type was recorded are listed as `unknown`
+// without an address; `keys eth show` and `keys cosmos show` still work
+// for them. Encrypted keys are unlocked with the password from
+// --password-file or GORC_KEYSTORE_PASSWORD, or one prompted for once on
+// the terminal; without one they are listed as locked.
+impl Runnable for ListKeysCmd {
+    fn run(&self) {
+        if self.json_schema && self.output != OutputFormat::Json {
+            error!("--json-schema requires -o json");
+            process::exit(1);
+        }
+        self.try_run().unwrap_or_else(|err| err.exit());
+    }
+}
+
+impl ListKeysCmd {
+    fn try_run(&self) -> Result<(), KeysError> {
+        let config = APP.config();
+
+        let mut password = keystore::SharedPassword::new(&self.password_file);
+        let mut keys = Vec::new();
+        let mut failed = 0;
+        for name in keystore::names(self.recursive)? {
+            let info = name.and_then(|name| {
+                let key_type = keystore::read_metadata(&name)?.key_type;
+                let public = match key_type {
+                    Some(KeyType::Eth) => password
+                        .load_key(&name)
+                        .and_then(|key| ShowEthKeyCmd::info(&name, &key))
+                        .map(|info| Some((info.address, info.fingerprint))),
+                    Some(KeyType::Cosmos) => password
+                        .load_key(&name)
+                        .and_then(|key| {
+                            ShowCosmosKeyCmd::info(&name, &key, config.cosmos.prefix.trim())
+                        })
+                        .map(|info| Some((info.address, info.fingerprint))),
+                    None => Ok(None),
+                };
+
+                let (address, fingerprint, locked) = match public {
+                    Ok(Some((address, fingerprint))) => (Some(address), Some(fingerprint), false),
+                    Ok(None) => (None, None, false),
+                    Err(KeysError::NoPassword { .. }) => {
+                        let address = match key_type {
+                            Some(KeyType::Eth) => keystore::stored_address(&name),
+                            _ => None,
+                        };
+                        (address, None, true)
+                    }
+                    Err(err) => return Err(err),
+                };
+                Ok(KeyInfo {
+                    name: name.to_string(),
+                    key_type,
+                    address,
+                    fingerprint,
+                    modified: keystore::key_modified(&name),
+                    locked,
+                })
+            });
+
+            match info {
+                Ok(info) => keys.push(info),
+                Err(err) => {
+                    warn!("skipping: {}", err);
+                    failed += 1;
+                }
+            }
+        }
+
+        self.sort_by
+            .sort(&mut keys, |info| info.modified.as_deref());
+
+        match self.output {
+            OutputFormat::Text => {
+                let key_types: Vec<String> = keys
+                    .iter()
+                    .map(|info| {
+                        info.key_type
+                            .map_or("unknown".to_string(), |t| t.to_string())
+                    })
+                    .collect();
+                let rows: Vec<Vec<&str>> = keys
+                    .iter()
+                    .zip(&key_types)
+                    .map(|(info, key_type)| {
+                        let mut row = vec![
+                            info.name.as_str(),
+                            key_type.as_str(),
+                            info.address.as_deref().unwrap_or("-"),
+                        ];
+                        if self.fingerprint {
+                            row.push(info.fingerprint.as_deref().unwrap_or("-"));
+                        }
+                        row.push(info.modified.as_deref().unwrap_or("-"));
+                        if info.locked {
+                            row.push(LOCKED);
+                        }
+                        row
+                    })
+                    .collect();
+                output::print_table(&rows);
+            }
+            OutputFormat::Json if self.json_schema => {
+                output::print_versioned_json(&KeyList { keys })
+            }
+            OutputFormat::Json => output::print_json(&keys),
+        }
+
+        if failed > 0 {
+            return Err(KeysError::Skipped(failed));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+//! Interactive menu for `gorc keys`, `gorc keys eth` and `gorc keys cosmos`
+//! run without a subcommand
+
+use super::{
+    keystore::{self, KeyType},
+    KeysCmd,
+};
+use crate::commands::GorcCmd;
+use abscissa_core::{Command, Options, Runnable};
+use std::io;
+
+/// Actions offered for either type of key, with the inputs they ask for
+const ACTIONS: &[(&str, &str)] = &[
+    ("add", "generate a new key"),
+    ("import", "import a key from a bip39-mnemonic"),
+    ("list", "list the stored keys"),
+    ("show", "show the address of a key"),
+    ("delete", "delete a key"),
+    ("rename", "rename a key"),
+];
+
+#[derive(Command, Debug, Default, Options)]
+pub struct MenuCmd {
+    #[options(help = "print help message")]
+    pub help: bool,
+}
+
+#[derive(Command, Debug, Default, Options)]
+pub struct EthMenuCmd {
+    #[options(help = "print help message")]
+    pub help: bool,
+}
+
+#[derive(Command, Debug, Default, Options)]
+pub struct CosmosMenuCmd {
+    #[options(help = "print help message")]
+    pub help: bool,
+}
+
+// Entry point for `gorc keys menu`, which `gorc keys` runs
+impl Runnable for MenuCmd {
+    fn run(&self) {
+        run_menu(None);
+    }
+}
+
+// Entry point for `gorc keys eth menu`, which `gorc keys eth` runs
+impl Runnable for EthMenuCmd {
+    fn run(&self) {
+        run_menu(Some(KeyType::Eth));
+    }
+}
+
+// Entry point for `gorc keys cosmos menu`, which `gorc keys cosmos` runs
+impl Runnable for CosmosMenuCmd {
+    fn run(&self) {
+        run_menu(Some(KeyType::Cosmos));
+    }
+}
+
+/// Ask for the type of key if not given, an action and its inputs, then run
+/// the command they make up.
+///
+/// Prompts go to stderr. Without a terminal on both stdin and stdout the
+/// usage of the command is printed instead, so scripts never block on it.
+fn run_menu(key_type: Option<KeyType>) {
+    if !atty::is(atty::Stream::Stdin) || !atty::is(atty::Stream::Stdout) {
+        let mut path = vec!["keys".to_string()];
+        path.extend(key_type.map(|key_type| key_type.to_string()));
+        GorcCmd::print_usage_and_exit(&path);
+    }
+
+    let key_type = match key_type {
+        Some(key_type) => key_type,
+        None => {
+            let types = ["eth\tethereum keys", "cosmos\tcosmos keys"];
+            match choose("type of key", &types) {
+                Some(0) => KeyType::Eth,
+                Some(_) => KeyType::Cosmos,
+                None => return,
+            }
+        }
+    };
+
+    let actions: Vec<String> = ACTIONS
+        .iter()
+        .map(|(action, description)| format!("{}\t{}", action, description))
+        .collect();
+    let action = match choose("action", &actions) {
+        Some(index) => ACTIONS[index].0,
+        None => return,
+    };
+
+    let inputs = match action {
+        "add" | "import" => prompt("name of the new key").map(|name| vec![name]),
+        "list" => Some(Vec::new()),
+        "show" | "delete" => choose_key(key_type).map(|name| vec![name]),
+        _ => choose_key(key_type)
+            .and_then(|name| prompt("new name").map(|new_name| vec![name, new_name])),
+    };
+    let inputs = match inputs {
+        Some(inputs) => inputs,
+        None => return,
+    };
+
+    let mut args = vec![key_type.to_string(), action.to_string()];
+    args.extend(inputs);
+    eprintln!("> gorc keys {}", args.join(" "));
+    KeysCmd::parse_args_default(&args)
+        .expect("Could not parse menu command")
+        .run();
+}
+
+/// Let the user pick one of the stored keys of the given type, including
+/// those whose type wasn't recorded
+fn choose_key(key_type: KeyType) -> Option<String> {
+    let names: Vec<String> = keystore::names(true)
+        .unwrap_or_else(|err| err.exit())
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|name| match keystore::has_type(name, key_type) {
+            Ok(has_type) => has_type,
+            Err(err) => {
+                eprintln!("Skipping key '{}': {}", name, err);
+                false
+            }
+        })
+        .map(|name| name.to_string())
+        .collect();
+
+    if names.is_empty() {
+        eprintln!("There are no {} keys in the keystore.", key_type);
+        return None;
+    }
+    choose("key", &names).map(|index| names[index].clone())
+}
+
+/// Print the numbered choices and read one by number or first word, asking
+/// again until the answer is valid. An empty answer or end of input cancels.
+fn choose(title: &str, choices: &[impl AsRef<str>]) -> Option<usize> {
+    eprintln!("Choose the {}:", title);
+    for (i, choice) in choices.iter().enumerate() {
+        eprintln!("  {}) {}", i + 1, choice.as_ref());
+    }
+
+    loop {
+        let answer = prompt("number or name, empty to quit")?;
+        match parse_choice(&answer, choices) {
+            Some(index) => return Some(index),
+            None => eprintln!("'{}' is not one of the choices.", answer),
+        }
+    }
+}
+
+/// Index of the choice given by its 1-based number or first word
+fn parse_choice(answer: &str, choices: &[impl AsRef<str>]) -> Option<usize> {
+    if let Ok(number) = answer.parse::<usize>() {
+        return Some(number)
+            .filter(|number| (1..=choices.len()).contains(number))
+            .map(|number| number - 1);
+    }
+
+    choices
+        .iter()
+        .position(|choice| choice.as_ref().split('\t').next() == Some(answer))
+}
+
+/// Ask for a line of input, `None` if it's empty or input ended
+fn prompt(label: &str) -> Option<String> {
+    eprint!("> {}: ", label);
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .expect("Could not read answer");
+
+    let answer = answer.trim();
+    if answer.is_empty() {
+        None
+    } else {
+        Some(answer.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_choice;
+
+    #[test]
+    fn parses_choice_by_number_or_name() {
+        let choices = ["add\tgenerate a new key", "list\tlist the stored keys"];
+        assert_eq!(parse_choice("1", &choices), Some(0));
+        assert_eq!(parse_choice("2", &choices), Some(1));
+        assert_eq!(parse_choice("list", &choices), Some(1));
+
+        assert_eq!(parse_choice("0", &choices), None);
+        assert_eq!(parse_choice("3", &choices), None);
+        assert_eq!(parse_choice("generate", &choices), None);
+    }
+}
@@ -0,0 +1,9 @@
+mod new;
+
+use abscissa_core::{Command, Options, Runnable};
+
+#[derive(Command, Debug, Options, Runnable)]
+pub enum MnemonicCmd {
+    #[options(help = "new")]
+    New(new::NewMnemonicCmd),
+}
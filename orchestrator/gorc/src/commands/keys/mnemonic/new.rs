@@ -0,0 +1,79 @@
+use crate::prelude::*;
+use abscissa_core::{Command, Options, Runnable};
+use rand_core::{OsRng, RngCore};
+use std::process;
+
+/// Words in a mnemonic when `--words` isn't given
+const DEFAULT_WORDS: usize = 24;
+
+#[derive(Command, Debug, Default, Options)]
+pub struct NewMnemonicCmd {
+    #[options(help = "number of words in the mnemonic (12|24, default: 24)")]
+    pub words: Option<usize>,
+}
+
+// Entry point for `gorc keys mnemonic new`
+//
+// Prints a fresh English bip39-mnemonic without storing anything in the
+// keystore.
+impl Runnable for NewMnemonicCmd {
+    fn run(&self) {
+        let words = self.words.unwrap_or(DEFAULT_WORDS);
+        let mnemonic = generate(words).unwrap_or_else(|err| {
+            error!("{}", err);
+            process::exit(1);
+        });
+
+        println!("{}", mnemonic);
+    }
+}
+
+/// Generate a bip39-mnemonic with the given number of words from the
+/// operating system's random number generator
+fn generate(words: usize) -> Result<String, String> {
+    let entropy_len = match words {
+        12 => 16,
+        24 => 32,
+        _ => return Err(format!("unsupported word count {}, expected 12|24", words)),
+    };
+
+    let mut entropy = vec![0u8; entropy_len];
+    OsRng
+        .try_fill_bytes(&mut entropy)
+        .map_err(|err| format!("could not read random entropy: {}", err))?;
+    check_entropy(&entropy)?;
+
+    let mnemonic = deep_space::Mnemonic::from_entropy(&entropy)
+        .map_err(|err| format!("could not encode mnemonic: {:?}", err))?;
+    Ok(mnemonic.to_string())
+}
+
+/// Reject entropy a working random number generator would practically never
+/// produce, i.e. every byte the same
+fn check_entropy(entropy: &[u8]) -> Result<(), String> {
+    if entropy.windows(2).all(|pair| pair[0] == pair[1]) {
+        return Err("random number generator returned degenerate entropy".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_entropy, generate};
+
+    #[test]
+    fn generates_valid_mnemonics() {
+        for &words in &[12, 24] {
+            let mnemonic = generate(words).unwrap();
+            assert_eq!(mnemonic.split(' ').count(), words);
+            assert!(mnemonic.parse::<deep_space::Mnemonic>().is_ok());
+        }
+
+        // Cross-check the checksum with the implementation keys are derived with.
+        let mnemonic = generate(24).unwrap();
+        assert!(bip32::Mnemonic::new(&mnemonic, Default::default()).is_ok());
+
+        assert!(generate(15).is_err());
+        assert!(check_entropy(&[0u8; 32]).is_err());
+    }
+}
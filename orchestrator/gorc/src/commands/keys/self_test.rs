@@ -0,0 +1,327 @@
+use super::{
+    cosmos::show as cosmos_show,
+    derivation,
+    eth::{
+        show as eth_show,
+        sign::sign_message,
+        verify::{verify_message, VerifyError},
+    },
+    keystore::{self, json},
+};
+use crate::{application::APP, config::GorcConfig, prelude::*};
+use abscissa_core::{Application, Command, Options, Runnable};
+use k256::{
+    ecdsa::{
+        signature::{Signer, Verifier},
+        Signature, SigningKey, VerifyingKey,
+    },
+    elliptic_curve::sec1::ToEncodedPoint,
+    pkcs8::{FromPrivateKey, ToPrivateKey},
+};
+use pkcs8::PrivateKeyDocument;
+use rand_core::OsRng;
+use sha3::{Digest, Keccak256};
+use std::{fs, path::Path, process};
+
+/// The all-`abandon` BIP39 test vector and the addresses other wallets derive
+/// from it along the default Ethereum and Cosmos paths
+const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon \
+                        abandon abandon abandon abandon abandon about";
+const ETH_ADDRESS: &str = "0x9858EfFD232B4033E47d90003D41EC34EcaEda94";
+const COSMOS_ADDRESS: &str = "cosmos19rl4cm2hmr8afy4kldpxz3fka4jguq0auqdal4";
+
+/// Message signed by the generated keys
+const MESSAGE: &[u8] = b"gorc keys self-test";
+
+/// Password the generated keys are encrypted with in the temporary keystore
+const PASSWORD: &str = "self-test";
+
+#[derive(Command, Debug, Default, Options)]
+pub struct SelfTestCmd {
+    #[options(help = "print help message")]
+    pub help: bool,
+}
+
+/// Outcome of one check: what was found on success, the problem otherwise
+type Check = (&'static str, Result<String, String>);
+
+// Entry point for `gorc keys self-test`
+//
+// Generates, stores, reloads and signs with an eth and a cosmos key in a
+// temporary keystore, which is removed afterwards; the configured keystore
+// is not touched. Prints a line for each check and exits non-zero if any
+// of them failed.
+impl Runnable for SelfTestCmd {
+    fn run(&self) {
+        let config = APP.config();
+        let params = keystore::configured_scrypt_params();
+
+        let dir = tempfile::tempdir().expect("Could not create temporary keystore");
+        let mut checks = checks(&config, params, dir.path());
+        checks.push((
+            "cleanup",
+            dir.close()
+                .map(|_| "removed the temporary keystore".to_string())
+                .map_err(|err| format!("could not remove the temporary keystore: {}", err)),
+        ));
+
+        let mut failed = 0;
+        for (check, result) in &checks {
+            match result {
+                Ok(detail) => println!("ok\t{}\t{}", check, detail),
+                Err(problem) => {
+                    println!("error\t{}\t{}", check, problem);
+                    failed += 1;
+                }
+            }
+        }
+
+        if failed > 0 {
+            error!("self-test failed: {} of {} checks", failed, checks.len());
+            process::exit(1);
+        }
+        info!("self-test passed: {} checks", checks.len());
+    }
+}
+
+/// Run every check, storing keys in `dir`
+fn checks(config: &GorcConfig, params: scrypt::Params, dir: &Path) -> Vec<Check> {
+    let eth_key = generate(&config.ethereum.key_derivation_path);
+    let cosmos_key = generate(&config.cosmos.key_derivation_path);
+
+    vec![
+        (
+            "eth.known_address",
+            known_address(
+                "m/44'/60'/0'/0/0",
+                |key| Ok(eth_show::address(key)),
+                ETH_ADDRESS,
+            ),
+        ),
+        (
+            "cosmos.known_address",
+            known_address(
+                "m/44'/118'/0'/0/0",
+                |key| cosmos_show::address(key, "cosmos").map_err(|err| err.to_string()),
+                COSMOS_ADDRESS,
+            ),
+        ),
+        (
+            "eth.generate",
+            eth_key
+                .as_ref()
+                .map_err(Clone::clone)
+                .and_then(check_eth_address),
+        ),
+        (
+            "eth.store",
+            eth_key
+                .as_ref()
+                .map_err(Clone::clone)
+                .and_then(|key| check_store(key, params, &dir.join("eth.pem"))),
+        ),
+        (
+            "eth.store_json",
+            eth_key
+                .as_ref()
+                .map_err(Clone::clone)
+                .and_then(|key| check_store_json(key, params, &dir.join("eth.json"))),
+        ),
+        (
+            "eth.sign",
+            eth_key
+                .as_ref()
+                .map_err(Clone::clone)
+                .and_then(check_eth_signature),
+        ),
+        (
+            "cosmos.generate",
+            cosmos_key
+                .as_ref()
+                .map_err(Clone::clone)
+                .and_then(|key| check_cosmos_address(key, config.cosmos.prefix.trim())),
+        ),
+        (
+            "cosmos.store",
+            cosmos_key
+                .as_ref()
+                .map_err(Clone::clone)
+                .and_then(|key| check_store(key, params, &dir.join("cosmos.pem"))),
+        ),
+        (
+            "cosmos.sign",
+            cosmos_key
+                .as_ref()
+                .map_err(Clone::clone)
+                .and_then(check_cosmos_signature),
+        ),
+    ]
+}
+
+/// Derive a key from a new mnemonic along the given path, checking that
+/// deriving it again gives the same key
+fn generate(path: &str) -> Result<k256::SecretKey, String> {
+    let path = derivation::derivation_path(path, None, None)?;
+    let mnemonic = bip32::Mnemonic::random(&mut OsRng, Default::default());
+
+    let key = derivation::derive_key(mnemonic.phrase(), &path)?;
+    let again = derivation::derive_key(mnemonic.phrase(), &path)?;
+    if key.to_bytes() != again.to_bytes() {
+        return Err("deriving the same mnemonic twice gave different keys".to_string());
+    }
+    Ok(key)
+}
+
+/// Derive the test vector's key along `path` and compare its address
+fn known_address(
+    path: &str,
+    address: impl Fn(&k256::SecretKey) -> Result<String, String>,
+    expected: &str,
+) -> Result<String, String> {
+    let path = derivation::derivation_path(path, None, None)?;
+    let key = derivation::derive_key(MNEMONIC, &path)?;
+    let address = address(&key)?;
+    if address == expected {
+        Ok(address)
+    } else {
+        Err(format!("derived {}, expected {}", address, expected))
+    }
+}
+
+/// The address clarity derives must match the Keccak-256 of the public key
+/// as computed by k256
+fn check_eth_address(key: &k256::SecretKey) -> Result<String, String> {
+    let address = eth_show::address(key);
+
+    let public_key = key.public_key().to_encoded_point(false);
+    let hash = Keccak256::digest(&public_key.as_bytes()[1..]);
+    let expected = clarity::Address::from_slice(&hash[12..]).map_err(|err| err.to_string())?;
+    if address == expected.to_string() {
+        Ok(address)
+    } else {
+        Err(format!("derived {}, expected {}", address, expected))
+    }
+}
+
+fn check_cosmos_address(key: &k256::SecretKey, prefix: &str) -> Result<String, String> {
+    let address = cosmos_show::address(key, prefix).map_err(|err| err.to_string())?;
+    if address.starts_with(&format!("{}1", prefix)) {
+        Ok(address)
+    } else {
+        Err(format!("{} does not have the prefix '{}'", address, prefix))
+    }
+}
+
+/// Encrypt the key as PEM into `path`, then read and decrypt it again
+fn check_store(
+    key: &k256::SecretKey,
+    params: scrypt::Params,
+    path: &Path,
+) -> Result<String, String> {
+    let document = key.to_pkcs8_der().map_err(|err| err.to_string())?;
+    let encrypted = keystore::encrypt_with_params(&document, PASSWORD, params);
+    keystore::write_secret_file(path, encrypted.to_pem().as_bytes())
+        .map_err(|err| err.to_string())?;
+
+    let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    if !keystore::is_encrypted(&contents) {
+        return Err(format!("{} is not encrypted", path.display()));
+    }
+    let document = pkcs8::EncryptedPrivateKeyDocument::from_pem(&contents)
+        .map_err(|err| err.to_string())?
+        .decrypt(PASSWORD)
+        .map_err(|err| err.to_string())?;
+    compare_reloaded(key, &document)
+}
+
+/// Encrypt the key as a JSON keystore into `path`, then read and decrypt it
+/// again
+fn check_store_json(
+    key: &k256::SecretKey,
+    params: scrypt::Params,
+    path: &Path,
+) -> Result<String, String> {
+    let encrypted = json::encrypt(key, PASSWORD, params);
+    keystore::write_secret_file(path, encrypted.as_bytes()).map_err(|err| err.to_string())?;
+
+    let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let reloaded = json::decrypt(&contents, PASSWORD)?;
+    let document = reloaded.to_pkcs8_der().map_err(|err| err.to_string())?;
+    compare_reloaded(key, &document)
+}
+
+fn compare_reloaded(
+    key: &k256::SecretKey,
+    document: &PrivateKeyDocument,
+) -> Result<String, String> {
+    let reloaded = k256::SecretKey::from_pkcs8_doc(document).map_err(|err| err.to_string())?;
+    if reloaded.to_bytes() == key.to_bytes() {
+        Ok(format!("reloaded key {}", keystore::fingerprint(&reloaded)))
+    } else {
+        Err("reloaded key differs from the stored one".to_string())
+    }
+}
+
+/// Sign with EIP-191 and recover the signer, also checking that a changed
+/// message doesn't verify
+fn check_eth_signature(key: &k256::SecretKey) -> Result<String, String> {
+    let address = eth_show::address(key);
+    let address: clarity::Address = address.parse().map_err(|_| "invalid address")?;
+    let key = clarity::PrivateKey::from_slice(&key.to_bytes()).map_err(|err| err.to_string())?;
+
+    let signature = sign_message(&key, MESSAGE).to_string();
+    match verify_message(&address, MESSAGE, &signature) {
+        Ok(()) => {}
+        Err(VerifyError::Mismatch(recovered)) => {
+            return Err(format!(
+                "signature recovered {}, expected {}",
+                recovered, address
+            ))
+        }
+        Err(VerifyError::Malformed(reason)) => {
+            return Err(format!("invalid signature: {}", reason))
+        }
+    }
+
+    if verify_message(&address, b"tampered", &signature).is_ok() {
+        return Err("signature verified for a different message".to_string());
+    }
+    Ok(format!("signature {}", signature))
+}
+
+/// Sign with ECDSA over SHA-256 the way Cosmos SDK transactions are signed,
+/// also checking that a changed message doesn't verify
+fn check_cosmos_signature(key: &k256::SecretKey) -> Result<String, String> {
+    let signing_key = SigningKey::from(key);
+    let verifying_key = VerifyingKey::from(&signing_key);
+
+    let signature: Signature = signing_key.sign(MESSAGE);
+    verifying_key
+        .verify(MESSAGE, &signature)
+        .map_err(|_| "signature did not verify".to_string())?;
+    if verifying_key.verify(b"tampered", &signature).is_ok() {
+        return Err("signature verified for a different message".to_string());
+    }
+    Ok(format!(
+        "signature 0x{}",
+        clarity::utils::bytes_to_hex_str(signature.as_ref())
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::checks;
+    use crate::config::GorcConfig;
+
+    #[test]
+    fn passes_checks() {
+        let dir = tempfile::tempdir().unwrap();
+        let params = scrypt::Params::new(1, 8, 1).unwrap();
+
+        let checks = checks(&GorcConfig::default(), params, dir.path());
+        for (check, result) in &checks {
+            assert!(result.is_ok(), "{}: {:?}", check, result);
+        }
+        assert_eq!(checks.len(), 9);
+    }
+}
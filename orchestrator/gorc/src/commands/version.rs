@@ -3,15 +3,54 @@
 #![allow(clippy::never_loop)]
 
 use super::GorcCmd;
+use crate::output::{self, OutputFormat};
 use abscissa_core::{Command, Options, Runnable};
+use serde::Serialize;
+
+/// Short hash of the commit gorc was built from
+pub const GIT_COMMIT: &str = env!("GORC_GIT_COMMIT");
+
+/// UTC time gorc was built at, in RFC 3339 format
+pub const BUILD_TIMESTAMP: &str = env!("GORC_BUILD_TIMESTAMP");
 
 /// `version` subcommand
 #[derive(Command, Debug, Default, Options)]
-pub struct VersionCmd {}
+pub struct VersionCmd {
+    #[options(short = "o", help = "output format (text|json)")]
+    pub output: OutputFormat,
+}
+
+/// Version and build metadata of the running binary
+#[derive(Debug, Serialize)]
+pub struct VersionInfo {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub git_commit: &'static str,
+    pub build_timestamp: &'static str,
+}
+
+impl VersionInfo {
+    pub fn current() -> Self {
+        Self {
+            name: GorcCmd::name(),
+            version: env!("CARGO_PKG_VERSION"),
+            git_commit: GIT_COMMIT,
+            build_timestamp: BUILD_TIMESTAMP,
+        }
+    }
+}
 
 impl Runnable for VersionCmd {
     /// Print version message
     fn run(&self) {
-        println!("{} {}", GorcCmd::name(), GorcCmd::version());
+        let info = VersionInfo::current();
+
+        match self.output {
+            OutputFormat::Text => println!(
+                "{} {} ({} {})",
+                info.name, info.version, info.git_commit, info.build_timestamp
+            ),
+            OutputFormat::Json => output::print_json(&info),
+        }
     }
 }
@@ -1,21 +1,246 @@
 use serde::{Deserialize, Serialize};
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use toml::{value::Table, Value};
+
+/// Prefix of the environment variables overriding configuration fields
+pub const ENV_PREFIX: &str = "GORC_";
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct GorcConfig {
+    #[serde(default = "default_keystore")]
     pub keystore: String,
+    #[serde(default)]
+    pub keystore_backend: KeystoreBackend,
+    #[serde(default)]
+    pub keystore_format: KeystoreFormat,
+    /// Refuse to run commands which change the keystore
+    #[serde(default)]
+    pub keystore_readonly: bool,
+    #[serde(default)]
+    pub keystore_encryption: KeystoreEncryptionSection,
     pub gravity: GravitySection,
     pub ethereum: EthereumSection,
     pub cosmos: CosmosSection,
+    /// Where the value of each field came from, recorded when the
+    /// configuration is loaded
+    #[serde(skip)]
+    pub sources: Vec<ResolvedField>,
 }
 
 impl Default for GorcConfig {
     fn default() -> Self {
         Self {
-            keystore: "/tmp/keystore".to_owned(),
+            keystore: default_keystore(),
+            keystore_backend: KeystoreBackend::default(),
+            keystore_format: KeystoreFormat::default(),
+            keystore_readonly: false,
+            keystore_encryption: KeystoreEncryptionSection::default(),
             gravity: GravitySection::default(),
             ethereum: EthereumSection::default(),
             cosmos: CosmosSection::default(),
+            sources: Vec::new(),
+        }
+    }
+}
+
+impl GorcConfig {
+    /// The keystore directory with a leading `~` and environment variables
+    /// expanded
+    pub fn keystore_dir(&self) -> Result<PathBuf, String> {
+        expand_path(&self.keystore, |name| env::var(name).ok())
+            .map_err(|err| format!("invalid keystore path '{}': {}", self.keystore, err))
+    }
+
+    /// Where the value of the dotted field came from, the default if it
+    /// wasn't recorded
+    pub fn source(&self, name: &str) -> Source {
+        self.sources
+            .iter()
+            .find(|field| field.name == name)
+            .map_or(Source::Default, |field| field.source)
+    }
+}
+
+/// Keystore used when neither `--keystore` nor the config file set one
+fn default_keystore() -> String {
+    default_keystore_in(|name| env::var(name).ok())
+}
+
+/// `$XDG_DATA_HOME/gorc/keystore`, falling back to
+/// `~/.local/share/gorc/keystore`, or `/tmp/keystore` if the home directory
+/// is unknown.
+///
+/// Keystores in the former default location `~/.gorc/keystore` keep being
+/// used until one exists in the new location.
+fn default_keystore_in(env: impl Fn(&str) -> Option<String>) -> String {
+    // Relative XDG paths are invalid and to be ignored, see the XDG Base
+    // Directory Specification.
+    let home = env("HOME").filter(|home| !home.is_empty());
+    let data_home = env("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| {
+            home.as_ref()
+                .map(|home| Path::new(home).join(".local/share"))
+        });
+
+    let path = match data_home {
+        Some(data_home) => data_home.join("gorc").join("keystore"),
+        None => return "/tmp/keystore".to_owned(),
+    };
+
+    if let Some(home) = home {
+        let legacy = Path::new(&home).join(".gorc").join("keystore");
+        if !path.exists() && legacy.is_dir() {
+            return legacy.to_string_lossy().into_owned();
+        }
+    }
+
+    path.to_string_lossy().into_owned()
+}
+
+/// Expand a leading `~` to the home directory and `$VAR` or `${VAR}` to the
+/// value of the environment variable.
+///
+/// `~user` is left as is; unset variables are an error rather than silently
+/// expanding to nothing.
+pub fn expand_path(path: &str, env: impl Fn(&str) -> Option<String>) -> Result<PathBuf, String> {
+    let var = |name: &str| env(name).ok_or_else(|| format!("${} is not set", name));
+
+    let mut expanded = String::new();
+    let mut rest = path;
+    if rest == "~" || rest.starts_with("~/") {
+        expanded.push_str(&var("HOME")?);
+        rest = &rest[1..];
+    }
+
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        let (name, len) = if let Some(braced) = after.strip_prefix('{') {
+            let end = braced
+                .find('}')
+                .ok_or_else(|| "unterminated ${".to_string())?;
+            (&braced[..end], end + 2)
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or_else(|| after.len());
+            (&after[..end], end)
+        };
+
+        if name.is_empty() {
+            // A lone `$` isn't a variable reference.
+            expanded.push('$');
+        } else {
+            expanded.push_str(&var(name)?);
+        }
+        rest = &after[len..];
+    }
+    expanded.push_str(rest);
+
+    Ok(PathBuf::from(expanded))
+}
+
+/// Where the keystore keeps private keys
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeystoreBackend {
+    /// Key files in the `keystore` directory
+    File,
+    /// The operating system's keyring, under the `gorc` service. Key
+    /// metadata and an index of the key names are still kept in the
+    /// `keystore` directory.
+    Os,
+}
+
+impl Default for KeystoreBackend {
+    fn default() -> Self {
+        KeystoreBackend::File
+    }
+}
+
+impl FromStr for KeystoreBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "file" => Ok(KeystoreBackend::File),
+            "os" => Ok(KeystoreBackend::Os),
+            _ => Err(format!(
+                "unknown keystore backend '{}', expected file|os",
+                s
+            )),
+        }
+    }
+}
+
+/// File format new Ethereum keys are stored in
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeystoreFormat {
+    /// PKCS#8 PEM files, optionally encrypted
+    Pem,
+    /// Web3 Secret Storage (v3 JSON keystore) files as used by geth and ethers
+    Json,
+}
+
+impl Default for KeystoreFormat {
+    fn default() -> Self {
+        KeystoreFormat::Pem
+    }
+}
+
+impl FromStr for KeystoreFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pem" => Ok(KeystoreFormat::Pem),
+            "json" => Ok(KeystoreFormat::Json),
+            _ => Err(format!(
+                "unknown keystore format '{}', expected pem|json",
+                s
+            )),
+        }
+    }
+}
+
+/// Key derivation function used to encrypt keys in the keystore
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kdf {
+    Scrypt,
+}
+
+/// Parameters used when encrypting new keys. Keys encrypted with other
+/// parameters can still be decrypted, as the parameters are stored with
+/// each key.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeystoreEncryptionSection {
+    pub kdf: Kdf,
+    /// scrypt CPU/memory cost, a power of two up to 32768
+    pub n: u32,
+    /// scrypt block size
+    pub r: u32,
+    /// scrypt parallelization
+    pub p: u32,
+}
+
+impl Default for KeystoreEncryptionSection {
+    fn default() -> Self {
+        Self {
+            kdf: Kdf::Scrypt,
+            n: 32768,
+            r: 8,
+            p: 1,
         }
     }
 }
@@ -66,4 +291,354 @@ impl Default for CosmosSection {
             prefix: "cosmos".to_owned(),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Where the effective value of a configuration field came from
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Default,
+    File,
+    Env,
+    Cli,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = match self {
+            Source::Default => "default",
+            Source::File => "file",
+            Source::Env => "env",
+            Source::Cli => "cli",
+        };
+        f.write_str(source)
+    }
+}
+
+/// Effective value of a configuration field
+#[derive(Clone, Debug, Serialize)]
+pub struct ResolvedField {
+    /// Dotted name, e.g. `ethereum.rpc`
+    pub name: String,
+    pub value: String,
+    pub source: Source,
+}
+
+/// Environment variable overriding the named field: `GORC_<FIELD>` for
+/// top-level fields and `GORC_<SECTION>_<FIELD>` for fields in a section,
+/// e.g. `GORC_KEYSTORE` or `GORC_ETHEREUM_RPC`.
+pub fn env_var(name: &str) -> String {
+    format!("{}{}", ENV_PREFIX, name.replace('.', "_").to_uppercase())
+}
+
+/// Load the configuration from the file at `path`, if any, overlaid with
+/// `GORC_*` environment variables and then command line overrides
+pub fn load(
+    path: Option<&Path>,
+    cli: &[(&str, Value)],
+) -> Result<(GorcConfig, Vec<ResolvedField>), String> {
+    let file = match path {
+        Some(path) => Some(
+            fs::read_to_string(path)
+                .map_err(|err| format!("could not read {}: {}", path.display(), err))?,
+        ),
+        None => None,
+    };
+
+    resolve(file.as_deref(), |name| env::var(name).ok(), cli).map_err(|err| match path {
+        Some(path) => format!("{}: {}", path.display(), err),
+        None => err,
+    })
+}
+
+/// Build the configuration from its layers, later ones overriding earlier
+/// ones: defaults, the config file's contents, environment variables looked
+/// up with `env`, then command line overrides of top-level fields given as
+/// `(field, value)`.
+///
+/// Returns the configuration along with the value and source of each field.
+pub fn resolve(
+    file: Option<&str>,
+    env: impl Fn(&str) -> Option<String>,
+    cli: &[(&str, Value)],
+) -> Result<(GorcConfig, Vec<ResolvedField>), String> {
+    let defaults = Value::try_from(GorcConfig::default()).expect("Could not serialize defaults");
+    let mut config = match defaults {
+        Value::Table(table) => table,
+        _ => unreachable!("config serializes to a table"),
+    };
+
+    let mut fields: Vec<(String, Source)> = Vec::new();
+    for (key, value) in &config {
+        match value {
+            Value::Table(section) => {
+                for field in section.keys() {
+                    fields.push((format!("{}.{}", key, field), Source::Default));
+                }
+            }
+            _ => fields.push((key.clone(), Source::Default)),
+        }
+    }
+
+    if let Some(file) = file {
+        let mut file: Table = toml::from_str(file).map_err(|err| err.to_string())?;
+        flatten_keystore_table(&mut file)?;
+        for (key, value) in file {
+            match (config.get_mut(&key), value) {
+                (Some(Value::Table(section)), Value::Table(overrides)) => {
+                    for (field, value) in overrides {
+                        mark(&mut fields, &format!("{}.{}", key, field), Source::File);
+                        section.insert(field, value);
+                    }
+                }
+                (_, value) => {
+                    mark(&mut fields, &key, Source::File);
+                    config.insert(key, value);
+                }
+            }
+        }
+    }
+
+    for (name, source) in fields.iter_mut() {
+        let var = env_var(name);
+        if let (Some(value), Some(current)) = (env(&var), lookup(&mut config, name)) {
+            *current = parse_env(current, &value).map_err(|err| format!("{}: {}", var, err))?;
+            *source = Source::Env;
+        }
+    }
+
+    for (name, value) in cli {
+        config.insert(name.to_string(), value.clone());
+        mark(&mut fields, name, Source::Cli);
+    }
+
+    let resolved = Value::Table(config.clone())
+        .try_into()
+        .map_err(|err: toml::de::Error| err.to_string())?;
+
+    // Every field is present now the config deserialized.
+    let fields = fields
+        .into_iter()
+        .map(|(name, source)| {
+            let value = match lookup(&mut config, &name).expect("Could not find config field") {
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            ResolvedField {
+                name,
+                value,
+                source,
+            }
+        })
+        .collect();
+
+    Ok((resolved, fields))
+}
+
+/// Fields of a `[keystore]` table and the top-level fields they set
+const KEYSTORE_TABLE_FIELDS: &[(&str, &str)] = &[
+    ("path", "keystore"),
+    ("backend", "keystore_backend"),
+    ("format", "keystore_format"),
+    ("readonly", "keystore_readonly"),
+    ("encryption", "keystore_encryption"),
+];
+
+/// Rewrite a `[keystore]` table in the config file into the top-level
+/// `keystore_*` fields, so `keystore.backend = "os"` sets `keystore_backend`.
+/// The `keystore = "<path>"` form is left as it is.
+fn flatten_keystore_table(file: &mut Table) -> Result<(), String> {
+    let table = match file.remove("keystore") {
+        Some(Value::Table(table)) => table,
+        Some(path) => {
+            file.insert("keystore".to_string(), path);
+            return Ok(());
+        }
+        None => return Ok(()),
+    };
+
+    for (field, value) in table {
+        let flat = KEYSTORE_TABLE_FIELDS
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, flat)| *flat)
+            .ok_or_else(|| {
+                format!(
+                    "unknown field `keystore.{}`, expected path|backend|format|readonly|encryption",
+                    field
+                )
+            })?;
+        if file.contains_key(flat) {
+            return Err(format!(
+                "`keystore.{}` and `{}` are the same setting, set only one",
+                field, flat
+            ));
+        }
+        file.insert(flat.to_string(), value);
+    }
+    Ok(())
+}
+
+/// Record the source of a field, ignoring unknown fields which fail to
+/// deserialize later on
+fn mark(fields: &mut [(String, Source)], name: &str, source: Source) {
+    if let Some((_, current)) = fields.iter_mut().find(|(field, _)| field == name) {
+        *current = source;
+    }
+}
+
+/// The value of a dotted field name
+fn lookup<'a>(config: &'a mut Table, name: &str) -> Option<&'a mut Value> {
+    match name.split_once('.') {
+        Some((section, field)) => config.get_mut(section)?.as_table_mut()?.get_mut(field),
+        None => config.get_mut(name),
+    }
+}
+
+/// Parse an environment variable as the same type as the value it replaces
+fn parse_env(current: &Value, value: &str) -> Result<Value, String> {
+    match current {
+        Value::Integer(_) => value
+            .parse()
+            .map(Value::Integer)
+            .map_err(|_| format!("expected an integer, got '{}'", value)),
+        Value::Boolean(_) => value
+            .parse()
+            .map(Value::Boolean)
+            .map_err(|_| format!("expected true or false, got '{}'", value)),
+        _ => Ok(Value::String(value.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_keystore_in, env_var, expand_path, resolve, KeystoreBackend, Source};
+    use std::collections::HashMap;
+    use toml::Value;
+
+    #[test]
+    fn expands_tilde_and_variables() {
+        let vars: HashMap<&str, &str> = [("HOME", "/home/orch"), ("NODE", "val0")]
+            .iter()
+            .copied()
+            .collect();
+        let env = |name: &str| vars.get(name).map(|value| value.to_string());
+        let expand = |path| expand_path(path, env).map(|path| path.display().to_string());
+
+        assert_eq!(expand("~").unwrap(), "/home/orch");
+        assert_eq!(expand("~/keys").unwrap(), "/home/orch/keys");
+        assert_eq!(expand("$HOME/$NODE").unwrap(), "/home/orch/val0");
+        assert_eq!(expand("/srv/${NODE}_keys").unwrap(), "/srv/val0_keys");
+        assert_eq!(expand("~other/keys").unwrap(), "~other/keys");
+        assert_eq!(expand("/a/~/$").unwrap(), "/a/~/$");
+        assert!(expand("$UNSET/keys").is_err());
+        assert!(expand("${NODE").is_err());
+    }
+
+    #[test]
+    fn defaults_keystore_to_xdg_data_home() {
+        let home = tempfile::tempdir().unwrap();
+        let home_dir = home.path().to_string_lossy().into_owned();
+        let in_home = |path: &str| home.path().join(path).to_string_lossy().into_owned();
+
+        let env = |xdg: Option<&str>| {
+            let home_dir = home_dir.clone();
+            let xdg = xdg.map(String::from);
+            move |name: &str| match name {
+                "HOME" => Some(home_dir.clone()),
+                "XDG_DATA_HOME" => xdg.clone(),
+                _ => None,
+            }
+        };
+
+        assert_eq!(default_keystore_in(env(Some("/xdg"))), "/xdg/gorc/keystore");
+        assert_eq!(
+            default_keystore_in(env(None)),
+            in_home(".local/share/gorc/keystore")
+        );
+        // Relative XDG paths are ignored.
+        assert_eq!(
+            default_keystore_in(env(Some("data"))),
+            in_home(".local/share/gorc/keystore")
+        );
+        assert_eq!(default_keystore_in(|_| None), "/tmp/keystore");
+
+        // An existing keystore in the old location is still found.
+        std::fs::create_dir_all(home.path().join(".gorc/keystore")).unwrap();
+        assert_eq!(default_keystore_in(env(None)), in_home(".gorc/keystore"));
+        std::fs::create_dir_all(home.path().join(".local/share/gorc/keystore")).unwrap();
+        assert_eq!(
+            default_keystore_in(env(None)),
+            in_home(".local/share/gorc/keystore")
+        );
+    }
+
+    #[test]
+    fn overlays_file_env_and_cli() {
+        let file = r#"
+            keystore = "/file/keystore"
+
+            [ethereum]
+            rpc = "http://file:8545"
+        "#;
+        let env = |name: &str| match name {
+            "GORC_KEYSTORE" => Some("/env/keystore".to_string()),
+            "GORC_ETHEREUM_RPC" => Some("http://env:8545".to_string()),
+            "GORC_KEYSTORE_ENCRYPTION_N" => Some("1024".to_string()),
+            _ => None,
+        };
+        let cli = [("keystore", Value::String("/cli/keystore".to_string()))];
+
+        let (mut config, fields) = resolve(Some(file), env, &cli).unwrap();
+        assert_eq!(config.keystore, "/cli/keystore");
+        assert_eq!(config.ethereum.rpc, "http://env:8545");
+        assert_eq!(config.keystore_encryption.n, 1024);
+        assert_eq!(config.cosmos.prefix, "cosmos");
+
+        config.sources = fields;
+        assert_eq!(config.source("keystore"), Source::Cli);
+        assert_eq!(config.source("ethereum.rpc"), Source::Env);
+        assert_eq!(config.source("cosmos.prefix"), Source::Default);
+
+        let (_, fields) = resolve(Some(file), |_| None, &[]).unwrap();
+        assert!(fields
+            .iter()
+            .any(|field| field.name == "ethereum.rpc" && field.source == Source::File));
+
+        assert_eq!(
+            env_var("keystore_encryption.n"),
+            "GORC_KEYSTORE_ENCRYPTION_N"
+        );
+        assert!(resolve(None, |name| env(name).map(|_| "x".to_string()), &[]).is_err());
+        assert!(resolve(Some("[ethereum]\nport = 1\n"), |_| None, &[]).is_err());
+    }
+
+    #[test]
+    fn accepts_keystore_table() {
+        let file = r#"
+            [keystore]
+            path = "/file/keystore"
+            backend = "os"
+            readonly = true
+
+            [keystore.encryption]
+            n = 1024
+        "#;
+        let (config, fields) = resolve(Some(file), |_| None, &[]).unwrap();
+        assert_eq!(config.keystore, "/file/keystore");
+        assert_eq!(config.keystore_backend, KeystoreBackend::Os);
+        assert!(config.keystore_readonly);
+        assert_eq!(config.keystore_encryption.n, 1024);
+        assert!(fields
+            .iter()
+            .any(|field| field.name == "keystore_backend" && field.source == Source::File));
+
+        let (config, _) = resolve(Some("keystore = \"/legacy\"\n"), |_| None, &[]).unwrap();
+        assert_eq!(config.keystore, "/legacy");
+        assert_eq!(config.keystore_backend, KeystoreBackend::File);
+
+        let both = "keystore_backend = \"file\"\n[keystore]\nbackend = \"os\"\n";
+        assert!(resolve(Some(both), |_| None, &[]).is_err());
+        assert!(resolve(Some("[keystore]\nbackends = \"os\"\n"), |_| None, &[]).is_err());
+    }
+}
@@ -1,10 +1,13 @@
 //! Error types
 
+use crate::prelude::*;
 use abscissa_core::error::{BoxError, Context};
 use std::{
     fmt::{self, Display},
     io,
     ops::Deref,
+    path::PathBuf,
+    process,
 };
 use thiserror::Error;
 
@@ -18,6 +21,14 @@ pub enum ErrorKind {
     /// Input/output error
     #[error("I/O error")]
     Io,
+
+    /// Key name can't be used in the keystore
+    #[error("invalid key name")]
+    KeyName,
+
+    /// Key could not be loaded from the keystore
+    #[error("could not load key")]
+    Key,
 }
 
 impl ErrorKind {
@@ -68,3 +79,82 @@ impl From<io::Error> for Error {
         ErrorKind::Io.context(err).into()
     }
 }
+
+/// Errors from the `keys` subcommands, reported as a single line on stderr
+#[derive(Debug, Error)]
+pub enum KeysError {
+    /// The keystore directory can't be listed
+    #[error("could not read keystore {}: {source}", .path.display())]
+    ReadKeystore { path: PathBuf, source: io::Error },
+
+    /// An entry of the keystore directory can't be read
+    #[error("could not read an entry of keystore {}: {source}", .path.display())]
+    ReadEntry { path: PathBuf, source: io::Error },
+
+    /// A key file name isn't valid UTF-8
+    #[error("key file {} does not have a UTF-8 name", .path.display())]
+    NonUtf8Name { path: PathBuf },
+
+    /// A key file has an extension but no name, e.g. `.pem`
+    #[error("key file {} has no name", .path.display())]
+    MissingStem { path: PathBuf },
+
+    /// A key file's name isn't a valid key name
+    #[error("key file {} has an invalid name", .path.display())]
+    InvalidName { path: PathBuf },
+
+    /// A key's metadata file can't be read, moved or removed
+    #[error("could not access key metadata {}: {source}", .path.display())]
+    Metadata { path: PathBuf, source: io::Error },
+
+    /// A key's metadata file isn't valid key metadata
+    #[error("key metadata {} is invalid: {source}", .path.display())]
+    InvalidMetadata {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+
+    /// A key could not be loaded
+    #[error("key '{name}': {source}")]
+    LoadKey { name: String, source: Error },
+
+    /// An encrypted key can't be unlocked: no password was supplied and
+    /// there's no terminal to prompt on
+    #[error("no password available for key '{name}': pass --password-file or set GORC_KEYSTORE_PASSWORD")]
+    NoPassword { name: String },
+
+    /// Keys were skipped while listing the keystore
+    #[error("{0} keys could not be loaded")]
+    Skipped(usize),
+
+    /// Other keystore error
+    #[error(transparent)]
+    Keystore(#[from] Error),
+}
+
+impl KeysError {
+    /// Exit code of the process: 74 (`EX_IOERR`) if the keystore or a key's
+    /// metadata can't be read, 65 (`EX_DATAERR`) for unusable key or metadata
+    /// files and 1 otherwise
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            KeysError::ReadKeystore { .. }
+            | KeysError::ReadEntry { .. }
+            | KeysError::Metadata { .. } => 74,
+            KeysError::NonUtf8Name { .. }
+            | KeysError::MissingStem { .. }
+            | KeysError::InvalidName { .. }
+            | KeysError::InvalidMetadata { .. } => 65,
+            KeysError::LoadKey { .. }
+            | KeysError::NoPassword { .. }
+            | KeysError::Skipped(_)
+            | KeysError::Keystore(_) => 1,
+        }
+    }
+
+    /// Print the error on stderr and exit with its exit code
+    pub fn exit(&self) -> ! {
+        error!("{}", self);
+        process::exit(self.exit_code())
+    }
+}
@@ -19,5 +19,7 @@ pub mod application;
 pub mod commands;
 pub mod config;
 pub mod error;
+pub mod output;
 pub mod prelude;
+pub mod qr;
 pub mod utils;
@@ -0,0 +1,167 @@
+//! Output formats for command results
+
+use serde::Serialize;
+use std::{env, str::FromStr};
+
+/// Environment variable which disables colored output when set, see
+/// <https://no-color.org>
+pub const NO_COLOR_ENV_VAR: &str = "NO_COLOR";
+
+/// ANSI styles of the table columns: bold names, cyan values
+const COLUMN_STYLES: [&str; 2] = ["\x1b[1m", "\x1b[36m"];
+
+/// ANSI sequence resetting the style
+const RESET_STYLE: &str = "\x1b[0m";
+
+/// Version of the JSON documents printed by the key list and show commands,
+/// bumped only when a field is removed, renamed or changes meaning
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Format used when writing command results to stdout
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OutputFormat {
+    /// Human-readable text
+    Text,
+
+    /// Machine parseable JSON
+    Json,
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        OutputFormat::Text
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("unknown output format '{}', expected text|json", s)),
+        }
+    }
+}
+
+/// Print the given value to stdout as a single line of JSON
+pub fn print_json<T: Serialize>(value: &T) {
+    let json = serde_json::to_string(value).expect("Could not serialize output");
+    println!("{}", json);
+}
+
+/// JSON document with the [`SCHEMA_VERSION`] ahead of the result's fields
+#[derive(Serialize)]
+struct Versioned<'a, T> {
+    schema_version: u32,
+    #[serde(flatten)]
+    value: &'a T,
+}
+
+/// Print the given struct to stdout as a single line of JSON, with a
+/// top-level `schema_version` field
+pub fn print_versioned_json<T: Serialize>(value: &T) {
+    print_json(&Versioned {
+        schema_version: SCHEMA_VERSION,
+        value,
+    });
+}
+
+/// Print `key: value` lines to stdout with the values aligned
+pub fn print_fields(fields: &[(&str, &str)]) {
+    let width = fields.iter().map(|(key, _)| key.len()).max().unwrap_or(0) + 1;
+    for (key, value) in fields {
+        println!("{:<width$} {}", format!("{}:", key), value, width = width);
+    }
+}
+
+/// Print rows to stdout as a table with every column but the last padded to
+/// its widest cell.
+///
+/// Cells are colored when stdout is a terminal and `NO_COLOR` isn't set.
+pub fn print_table(rows: &[Vec<&str>]) {
+    print!("{}", format_table(rows, use_color()));
+}
+
+/// Whether stdout should be colored
+pub fn use_color() -> bool {
+    let no_color = env::var_os(NO_COLOR_ENV_VAR).map_or(false, |value| !value.is_empty());
+    !no_color && atty::is(atty::Stream::Stdout)
+}
+
+fn format_table(rows: &[Vec<&str>], color: bool) -> String {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut out = String::new();
+    for row in rows {
+        let mut line = String::new();
+        for (column, cell) in row.iter().enumerate() {
+            if column > 0 {
+                line.push_str("  ");
+            }
+
+            // Pad before styling so the escape sequences don't count
+            // towards the width.
+            let padded = if column + 1 < row.len() {
+                format!("{:<width$}", cell, width = widths[column])
+            } else {
+                cell.to_string()
+            };
+            if color {
+                let style = COLUMN_STYLES[column.min(COLUMN_STYLES.len() - 1)];
+                line.push_str(&format!("{}{}{}", style, padded, RESET_STYLE));
+            } else {
+                line.push_str(&padded);
+            }
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_table, Versioned, SCHEMA_VERSION};
+    use serde::Serialize;
+
+    #[test]
+    fn aligns_table_columns() {
+        let rows = vec![vec!["orchestrator", "0x01"], vec!["val", "0x02"]];
+        assert_eq!(
+            format_table(&rows, false),
+            "orchestrator  0x01\nval           0x02\n"
+        );
+        assert_eq!(
+            format_table(&rows[1..], true),
+            "\x1b[1mval\x1b[0m  \x1b[36m0x02\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn versions_json_documents() {
+        #[derive(Serialize)]
+        struct Keys {
+            keys: Vec<&'static str>,
+        }
+
+        let document = Versioned {
+            schema_version: SCHEMA_VERSION,
+            value: &Keys { keys: vec!["orch"] },
+        };
+        assert_eq!(
+            serde_json::to_string(&document).unwrap(),
+            r#"{"schema_version":1,"keys":["orch"]}"#
+        );
+    }
+}
@@ -0,0 +1,37 @@
+//! QR codes for printing addresses to the terminal
+
+use qrcode::{render::unicode::Dense1x2, types::QrError, EcLevel, QrCode};
+
+/// Encode the data at error correction level L and render it with Unicode
+/// half blocks, two rows of modules per line.
+///
+/// Light modules are drawn as blocks so the code scans on the usual light on
+/// dark terminal.
+pub fn render(data: &[u8]) -> Result<String, QrError> {
+    let code = QrCode::with_error_correction_level(data, EcLevel::L)?;
+    let mut rendered = code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build();
+    rendered.push('\n');
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+
+    #[test]
+    fn renders_address_with_quiet_zone() {
+        let rendered = render(b"0x2c7536E3605D9C16a7a3D7b1898e529396a65c23").unwrap();
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        // A version 3 symbol is 29 modules wide, plus 4 on each side.
+        assert_eq!(lines.len(), 19);
+        assert!(lines.iter().all(|line| line.chars().count() == 37));
+        assert!(lines[0].chars().all(|c| c == '█'));
+        // The top left finder pattern starts after the quiet zone.
+        assert!(lines[2].starts_with("████ ▄▄▄▄▄ █"));
+    }
+}
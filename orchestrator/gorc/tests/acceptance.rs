@@ -28,7 +28,7 @@ use once_cell::sync::Lazy;
 /// the runner acquire a mutex when executing commands and inspecting
 /// exit statuses, serializing what would otherwise be multithreaded
 /// invocations as `cargo test` executes tests in parallel by default.
-pub static RUNNER: Lazy<CmdRunner> = Lazy::new(|| CmdRunner::default());
+pub static RUNNER: Lazy<CmdRunner> = Lazy::new(CmdRunner::default);
 
 /// Use `GorcConfig::default()` value if no config or args
 // #[test]
@@ -87,5 +87,18 @@ pub static RUNNER: Lazy<CmdRunner> = Lazy::new(|| CmdRunner::default());
 fn version_no_args() {
     let mut runner = RUNNER.clone();
     let mut cmd = runner.arg("version").capture_stdout().run();
-    cmd.stdout().expect_regex(r"\A\w+ [\d\.\-]+\z");
+    cmd.stdout()
+        .expect_regex(r"\A\w+ [\d\.\-]+ \(\w+ \d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z\)\z");
+}
+
+/// Build metadata is available as JSON for monitoring
+#[test]
+fn version_json() {
+    let mut runner = RUNNER.clone();
+    let mut cmd = runner
+        .args(&["version", "-o", "json"])
+        .capture_stdout()
+        .run();
+    cmd.stdout()
+        .expect_regex(r#"\A\{"name":"gorc","version":"[\d\.\-]+","git_commit":"\w+","build_timestamp":"[\dT:\-]+Z"\}\z"#);
 }